@@ -1,7 +1,11 @@
 use anyhow::{anyhow, Context};
-use fcc::{compliance::check_compliance, FlatConfig, FlatConfigCompliance};
+use fcc::{
+    compliance::{check_compliance_with_vars, ComplianceReport, ComplianceSummary, Variables},
+    FlatConfig, FlatConfigCompliance,
+};
 use serde::{Deserialize, Serialize};
-use std::{env, fs, process, str::FromStr};
+use serde_json::Value;
+use std::{collections::BTreeMap, env, fs, process, str::FromStr};
 
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -19,12 +23,18 @@ struct ModuleArgs {
 
     policy: String,
     configuration: String,
+
+    /// Values of the policy {{ var }} placeholders
+    #[serde(default)]
+    vars: BTreeMap<String, Value>,
 }
 
 #[derive(Clone, Serialize, Default)]
 struct Response {
     msg: String,
     results: Vec<String>,
+    summary: ComplianceSummary,
+    report: ComplianceReport,
     changed: bool,
     failed: bool,
 }
@@ -58,22 +68,37 @@ fn run_module() -> anyhow::Result<Response> {
     let config = FlatConfig::from_str(&module_args.configuration)?;
     let policy = FlatConfigCompliance::from_str(&module_args.policy)?;
 
-    let results = check_compliance(policy, config);
-    let mut compliance_failed = false;
+    let vars = module_args
+        .vars
+        .into_iter()
+        .map(|(name, value)| match value {
+            Value::String(s) => Ok((name, s)),
+            Value::Number(_) | Value::Bool(_) => Ok((name, value.to_string())),
+            _ => Err(anyhow!(
+                "variable '{}' must be a string, a number or a boolean",
+                name
+            )),
+        })
+        .collect::<anyhow::Result<Variables>>()?;
+
+    let report = check_compliance_with_vars(policy, config, &vars)?;
+    let compliance_failed = !report.is_compliant();
     Ok(Response {
         msg: String::new(),
-        results: results
-            .iter()
-            .map(|f| {
-                if f.result.is_ok() {
-                    format!("OK-{}", f)
+        results: report
+            .flatten()
+            .map(|(path, f)| {
+                let status = if f.result.is_ok() { "OK" } else { "ERR" };
+                if path.is_empty() {
+                    format!("{}-{}", status, f)
                 } else {
-                    compliance_failed = true;
-                    format!("ERR-{}", f)
+                    format!("{}-{} > {}", status, path.join(" > "), f)
                 }
             })
             .collect(),
+        summary: report.summary,
         changed: matches!(module_args.module_return, ModuleReturn::Changed) && compliance_failed,
         failed: matches!(module_args.module_return, ModuleReturn::Failed) && compliance_failed,
+        report,
     })
 }
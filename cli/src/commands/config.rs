@@ -1,17 +1,24 @@
 use std::{
-    fs::{read_to_string, File},
+    fs::File,
     io::{stdin, BufReader, IsTerminal, Read},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::ExitCode,
-    str::FromStr,
 };
 
 use anyhow::{anyhow, Result};
 use clap::Subcommand;
-use fcc::{compliance::check_compliance, FlatConfig, FlatConfigCompliance};
-use log::{debug, error, info};
+use fcc::{
+    compliance::{check_compliance, remediation::remediate, Severity},
+    error::FlatConfigError,
+    FlatConfig, FlatConfigCompliance,
+};
+use log::{debug, error, info, warn};
 
-use crate::Cli;
+use crate::{
+    misc::{format_result, is_failure, is_stdin, read_vars},
+    output::OutputArgs,
+    severity_parser, Cli,
+};
 
 #[derive(Subcommand)]
 pub enum ConfigCommands {
@@ -28,6 +35,35 @@ pub enum ConfigCommands {
         /// Skip error when reading policy file
         #[arg(short, long, action)]
         ignore_invalid_policy: bool,
+
+        /// Only fail on failures rated SEVERITY or more, unrated failures are ignored
+        #[arg(long, value_name = "SEVERITY", value_parser = severity_parser())]
+        fail_on: Option<Severity>,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Print the commands which would make the configuration compliant with one or several policies
+    Remediate {
+        /// The path to the configuration file to read, use - to read from stdin (must not be a tty)
+        #[arg(value_name = "CONFIG", required = true)]
+        config: PathBuf,
+
+        /// The path to the policy file to use to check compliance, accept multiple paths
+        #[arg(value_name = "POLICY", required = true)]
+        policies: Vec<PathBuf>,
+
+        /// Skip error when reading policy file
+        #[arg(short, long, action)]
+        ignore_invalid_policy: bool,
+    },
+
+    /// Print the configuration as set commands
+    ToSet {
+        /// The path to the configuration file to read, use - to read from stdin (must not be a tty)
+        #[arg(value_name = "CONFIG", required = true)]
+        config: PathBuf,
     },
 }
 
@@ -38,19 +74,27 @@ impl ConfigCommands {
                 config,
                 policies,
                 ignore_invalid_policy,
-            } => config_subcommand_check(cli, config, policies, *ignore_invalid_policy),
+                fail_on,
+                output,
+            } => config_subcommand_check(
+                cli,
+                config,
+                policies,
+                *ignore_invalid_policy,
+                *fail_on,
+                output,
+            ),
+            ConfigCommands::Remediate {
+                config,
+                policies,
+                ignore_invalid_policy,
+            } => config_subcommand_remediate(cli, config, policies, *ignore_invalid_policy),
+            ConfigCommands::ToSet { config } => config_subcommand_to_set(cli, config),
         }
     }
 }
 
-fn config_subcommand_check(
-    _cli: &Cli,
-    config_path: &PathBuf,
-    policies: &[PathBuf],
-    ignore_invalid_policy: bool,
-) -> Result<ExitCode> {
-    debug!("config_subcommand_check config {}", config_path.display());
-
+fn read_config(cli: &Cli, config_path: &PathBuf) -> Result<FlatConfig> {
     if config_path.is_dir() {
         return Err(anyhow!(
             "CONFIG can't be a directory. {}",
@@ -59,7 +103,7 @@ fn config_subcommand_check(
     }
 
     let mut raw_config = String::new();
-    if *config_path == PathBuf::from("-") {
+    if is_stdin(config_path) {
         if stdin().is_terminal() {
             return Err(anyhow!("use - to read from stdin (must not be a tty)."));
         }
@@ -71,8 +115,48 @@ fn config_subcommand_check(
         read.read_to_string(&mut raw_config)?;
     }
 
-    let config = FlatConfig::from_str(&raw_config)?;
+    let config = FlatConfig::from_str_with_options(&raw_config, cli.config_parse_option())?
+        .with_source(&config_path.display().to_string());
+    Ok(cli.align_config(config))
+}
+
+/// Read a policy file, `None` if it is invalid and `ignore_invalid_policy` is set.
+fn read_policy(
+    cli: &Cli,
+    path: &Path,
+    ignore_invalid_policy: bool,
+) -> Result<Option<FlatConfigCompliance>> {
+    match FlatConfigCompliance::from_file_with_options(path, cli.policy_parse_option()) {
+        Ok(fcc) => Ok(Some(fcc)),
+        Err(FlatConfigError::IO(_)) if ignore_invalid_policy => {
+            error!("Unable to read policy: {}", path.display());
+            Ok(None)
+        }
+        Err(_) if ignore_invalid_policy => {
+            error!(
+                "Unable to parse policy: {}. Use \"lint\" to see details.",
+                path.display()
+            );
+            Ok(None)
+        }
+        Err(err) => Err(anyhow!(err)),
+    }
+}
+
+fn config_subcommand_check(
+    cli: &Cli,
+    config_path: &PathBuf,
+    policies: &[PathBuf],
+    ignore_invalid_policy: bool,
+    fail_on: Option<Severity>,
+    output: &OutputArgs,
+) -> Result<ExitCode> {
+    debug!("config_subcommand_check config {}", config_path.display());
+
+    let config = read_config(cli, config_path)?;
+    let vars = read_vars(cli.vars.as_deref())?;
 
+    let mut reports = vec![];
     let mut return_error = false;
     for path in policies {
         debug!(
@@ -80,40 +164,27 @@ fn config_subcommand_check(
             path.display(),
             config_path.display()
         );
-        match read_to_string(path) {
-            Ok(raw_policy) => match FlatConfigCompliance::from_str(&raw_policy) {
-                Ok(fcc) => {
-                    let result = check_compliance(fcc, config.clone());
-                    for r in result {
-                        if r.result.is_err() {
-                            return_error = true;
-                            error!("{}", r);
-                        } else {
-                            info!("{}", r);
-                        }
-                    }
-                }
-                Err(err) => {
-                    if ignore_invalid_policy {
-                        error!(
-                            "Unable to parse policy: {}. Use \"lint\" to see details.",
-                            path.display()
-                        );
-                        continue;
-                    } else {
-                        return Err(anyhow!(err));
-                    }
-                }
-            },
-            Err(err) => {
-                if ignore_invalid_policy {
-                    error!("Unable to read policy: {}", path.display());
-                    continue;
-                } else {
-                    return Err(anyhow!(err));
-                }
+        let Some(fcc) = read_policy(cli, path, ignore_invalid_policy)? else {
+            continue;
+        };
+        let fcc = cli.align_policy(fcc.with_variables(&vars)?);
+        let report = check_compliance(fcc, config.clone());
+        for (p, r) in report.flatten() {
+            if r.result.is_err() {
+                error!("{}", format_result(&p, r));
+            } else {
+                info!("{}", format_result(&p, r));
             }
         }
+        if is_failure(&report, fail_on) {
+            return_error = true;
+        }
+        info!("{}: {}", path.display(), report.summary);
+        reports.push(report);
+    }
+
+    if !output.is_text() {
+        output.write(&reports)?;
     }
 
     if return_error {
@@ -122,3 +193,51 @@ fn config_subcommand_check(
         Ok(ExitCode::SUCCESS)
     }
 }
+
+fn config_subcommand_remediate(
+    cli: &Cli,
+    config_path: &PathBuf,
+    policies: &[PathBuf],
+    ignore_invalid_policy: bool,
+) -> Result<ExitCode> {
+    debug!(
+        "config_subcommand_remediate config {}",
+        config_path.display()
+    );
+
+    let config = read_config(cli, config_path)?;
+    let vars = read_vars(cli.vars.as_deref())?;
+
+    for path in policies {
+        debug!(
+            "config_subcommand_remediate policy {} against {}",
+            path.display(),
+            config_path.display()
+        );
+        let Some(fcc) = read_policy(cli, path, ignore_invalid_policy)? else {
+            continue;
+        };
+        let fcc = cli.align_policy(fcc.with_variables(&vars)?);
+        let report = check_compliance(fcc, config.clone());
+        let remediation = remediate(&report);
+        for item in &remediation.skipped {
+            warn!(
+                "{}: unable to remediate policy \"{}\"",
+                path.display(),
+                item.get_item_key()
+            );
+        }
+        print!("{}", remediation);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn config_subcommand_to_set(cli: &Cli, config_path: &PathBuf) -> Result<ExitCode> {
+    debug!("config_subcommand_to_set config {}", config_path.display());
+
+    let config = read_config(cli, config_path)?;
+    print!("{}", config.to_set_commands());
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -1,17 +1,23 @@
 use std::{
-    fs::{read_to_string, File},
+    fs::read_to_string,
     io::{stdin, BufReader, IsTerminal, Read},
     path::{Path, PathBuf},
     process::ExitCode,
-    str::FromStr,
 };
 
 use anyhow::{anyhow, Result};
 use clap::Subcommand;
-use fcc::{compliance::check_compliance, FlatConfig, FlatConfigCompliance};
+use fcc::{
+    compliance::{check_compliance, Severity},
+    FlatConfig, FlatConfigCompliance,
+};
 use log::{debug, error, info, warn};
 
-use crate::Cli;
+use crate::{
+    misc::{format_result, is_failure, is_stdin, read_vars},
+    output::OutputArgs,
+    severity_parser, Cli,
+};
 
 #[derive(Subcommand)]
 pub enum PolicyCommands {
@@ -35,6 +41,13 @@ pub enum PolicyCommands {
         /// Skip error when reading config file
         #[arg(short, long, action)]
         ignore_invalid_config: bool,
+
+        /// Only fail on failures rated SEVERITY or more, unrated failures are ignored
+        #[arg(long, value_name = "SEVERITY", value_parser = severity_parser())]
+        fail_on: Option<Severity>,
+
+        #[command(flatten)]
+        output: OutputArgs,
     },
 }
 
@@ -46,21 +59,28 @@ impl PolicyCommands {
                 policy,
                 configs,
                 ignore_invalid_config,
-            } => policy_subcommand_check(cli, policy, configs, *ignore_invalid_config),
+                fail_on,
+                output,
+            } => policy_subcommand_check(
+                cli,
+                policy,
+                configs,
+                *ignore_invalid_config,
+                *fail_on,
+                output,
+            ),
         }
     }
 }
 
-fn policy_subcommand_lint(_cli: &Cli, policies: &[PathBuf]) -> Result<ExitCode> {
+fn policy_subcommand_lint(cli: &Cli, policies: &[PathBuf]) -> Result<ExitCode> {
     for path in policies {
         debug!("policy_subcommand_lint path {}", path.display());
-        let mut data = String::new();
-
         if path.is_dir() {
             continue;
         }
 
-        if *path == PathBuf::from("-") {
+        let policy = if is_stdin(path) {
             if policies.len() != 1 {
                 return Err(anyhow!("Reading from stdin one time is enough."));
             }
@@ -69,14 +89,16 @@ fn policy_subcommand_lint(_cli: &Cli, policies: &[PathBuf]) -> Result<ExitCode>
                 return Err(anyhow!("\"-\" nothing to read from there."));
             }
 
+            let mut data = String::new();
             let mut read = BufReader::new(stdin().lock());
             read.read_to_string(&mut data)?;
+            FlatConfigCompliance::from_str_with_options(&data, cli.policy_parse_option())
         } else {
-            let mut read = BufReader::new(File::open(path)?);
-            read.read_to_string(&mut data)?;
-        }
+            // Includes are relative to the policy file
+            FlatConfigCompliance::from_file_with_options(path, cli.policy_parse_option())
+        };
 
-        match FlatConfigCompliance::from_str(&data) {
+        match policy {
             Ok(_) => {
                 info!("{}: Syntax OK.", path.display());
             }
@@ -89,10 +111,12 @@ fn policy_subcommand_lint(_cli: &Cli, policies: &[PathBuf]) -> Result<ExitCode>
 }
 
 fn policy_subcommand_check(
-    _cli: &Cli,
+    cli: &Cli,
     policy_path: &Path,
     configs: &[PathBuf],
     ignore_invalid_config: bool,
+    fail_on: Option<Severity>,
+    output: &OutputArgs,
 ) -> Result<ExitCode> {
     debug!("policy_subcommand_check policy {}", policy_path.display());
 
@@ -103,21 +127,23 @@ fn policy_subcommand_check(
         ));
     }
 
-    let mut raw_policy = String::new();
-    if *policy_path == PathBuf::from("-") {
+    let policy = if is_stdin(policy_path) {
         if stdin().is_terminal() {
             return Err(anyhow!("use - to read from stdin (must not be a tty)."));
         }
 
+        let mut raw_policy = String::new();
         let mut read = BufReader::new(stdin().lock());
         read.read_to_string(&mut raw_policy)?;
+        FlatConfigCompliance::from_str_with_options(&raw_policy, cli.policy_parse_option())?
+            .with_source(&policy_path.display().to_string())
     } else {
-        let mut read = BufReader::new(File::open(policy_path)?);
-        read.read_to_string(&mut raw_policy)?;
-    }
-
-    let policy = FlatConfigCompliance::from_str(&raw_policy)?;
+        FlatConfigCompliance::from_file_with_options(policy_path, cli.policy_parse_option())?
+    };
+    let vars = read_vars(cli.vars.as_deref())?;
+    let policy = cli.align_policy(policy.with_variables(&vars)?);
 
+    let mut reports = vec![];
     let mut return_error = false;
     for path in configs {
         debug!(
@@ -126,29 +152,41 @@ fn policy_subcommand_check(
             path.display()
         );
         match read_to_string(path) {
-            Ok(raw_config) => match FlatConfig::from_str(&raw_config) {
-                Ok(config) => {
-                    let result = check_compliance(policy.clone(), config);
-                    for r in result {
-                        if r.result.is_err() {
+            Ok(raw_config) => {
+                match FlatConfig::from_str_with_options(&raw_config, cli.config_parse_option()) {
+                    Ok(config) => {
+                        let config =
+                            cli.align_config(config.with_source(&path.display().to_string()));
+                        let report = check_compliance(policy.clone(), config);
+                        for (p, r) in report.flatten() {
+                            if r.result.is_err() {
+                                if output.is_text() {
+                                    eprintln!("{}", format_result(&p, r));
+                                }
+                                error!("{}", format_result(&p, r));
+                            } else {
+                                if output.is_text() {
+                                    println!("{}", format_result(&p, r));
+                                }
+                                info!("{}", format_result(&p, r));
+                            }
+                        }
+                        if is_failure(&report, fail_on) {
                             return_error = true;
-                            eprintln!("{}", r);
-                            error!("{}", r);
-                        } else {
-                            println!("{}", r);
-                            info!("{}", r);
                         }
+                        info!("{}: {}", path.display(), report.summary);
+                        reports.push(report);
                     }
-                }
-                Err(err) => {
-                    if ignore_invalid_config {
-                        error!("Unable to parse config: {}.", path.display());
-                        continue;
-                    } else {
-                        return Err(anyhow!(err));
+                    Err(err) => {
+                        if ignore_invalid_config {
+                            error!("Unable to parse config: {}.", path.display());
+                            continue;
+                        } else {
+                            return Err(anyhow!(err));
+                        }
                     }
                 }
-            },
+            }
             Err(err) => {
                 if ignore_invalid_config {
                     error!("Unable to read config: {}", path.display());
@@ -160,6 +198,10 @@ fn policy_subcommand_check(
         }
     }
 
+    if !output.is_text() {
+        output.write(&reports)?;
+    }
+
     if return_error {
         Ok(ExitCode::FAILURE)
     } else {
@@ -1,14 +1,18 @@
 mod commands;
 mod misc;
+mod output;
 
-use std::process::ExitCode;
+use std::{path::PathBuf, process::ExitCode, str::FromStr};
 
+use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::Parser;
 use clap_verbosity_flag::{InfoLevel, Verbosity};
 use commands::Commands;
+use fcc::{compliance::Severity, Dialect, FlatConfig, FlatConfigCompliance, ParseOption};
 use human_panic::{setup_panic, Metadata};
 use log::error;
 use misc::get_log_level;
+use strum::VariantNames;
 
 #[derive(Parser)]
 #[command(name = env!("CARGO_BIN_NAME"))]
@@ -18,11 +22,75 @@ struct Cli {
     #[command(flatten)]
     verbose: Verbosity<InfoLevel>,
 
+    /// Syntax used by configurations and policies to express hierarchy
+    #[arg(long, global = true, default_value_t, value_parser = dialect_parser())]
+    dialect: Dialect,
+
+    /// Syntax used by policies, same as --dialect if not set
+    #[arg(long, global = true, value_parser = dialect_parser())]
+    policy_dialect: Option<Dialect>,
+
+    /// YAML file with the values of the policies {{ var }} placeholders
+    #[arg(long, global = true, value_name = "FILE")]
+    vars: Option<PathBuf>,
+
+    /// Collapse the whitespaces between words of configurations lines, policies use the
+    /// per item #[normalize-whitespace] option
+    #[arg(long, global = true)]
+    normalize_whitespace: bool,
+
     // Subcommand
     #[command(subcommand)]
     command: Commands,
 }
 
+impl Cli {
+    fn policy_dialect(&self) -> Dialect {
+        self.policy_dialect.unwrap_or(self.dialect)
+    }
+
+    fn config_parse_option(&self) -> ParseOption {
+        ParseOption {
+            dialect: self.dialect,
+            normalize_whitespace: self.normalize_whitespace,
+            ..Default::default()
+        }
+    }
+
+    fn policy_parse_option(&self) -> ParseOption {
+        ParseOption {
+            dialect: self.policy_dialect(),
+            ..Default::default()
+        }
+    }
+
+    /// Split a configuration into one level per word when policies use set commands.
+    fn align_config(&self, config: FlatConfig) -> FlatConfig {
+        if self.policy_dialect() == Dialect::Set && self.dialect != Dialect::Set {
+            config.into_set_hierarchy()
+        } else {
+            config
+        }
+    }
+
+    /// Split a policy into one level per word when configurations use set commands.
+    fn align_policy(&self, policy: FlatConfigCompliance) -> FlatConfigCompliance {
+        if self.dialect == Dialect::Set && self.policy_dialect() != Dialect::Set {
+            policy.into_set_hierarchy()
+        } else {
+            policy
+        }
+    }
+}
+
+fn dialect_parser() -> impl TypedValueParser<Value = Dialect> {
+    PossibleValuesParser::new(Dialect::VARIANTS).map(|s| Dialect::from_str(&s).unwrap())
+}
+
+fn severity_parser() -> impl TypedValueParser<Value = Severity> {
+    PossibleValuesParser::new(Severity::VARIANTS).map(|s| Severity::from_str(&s).unwrap())
+}
+
 #[tokio::main]
 async fn main() -> ExitCode {
     setup_panic!(Metadata::new(
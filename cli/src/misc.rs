@@ -1,6 +1,9 @@
-use std::io::IsTerminal;
+use std::{collections::BTreeMap, fs::File, io::IsTerminal, path::Path};
 
+use anyhow::{anyhow, Result};
+use fcc::compliance::{ComplianceReport, ItemComplianceResult, Severity, Variables};
 use log::LevelFilter;
+use serde_yaml::Value;
 
 use crate::{commands::Commands, Cli};
 
@@ -17,3 +20,53 @@ pub fn get_log_level(cli: &Cli) -> LevelFilter {
 
     cli.verbose.log_level_filter()
 }
+
+pub fn format_result(path: &[&str], result: &ItemComplianceResult) -> String {
+    if path.is_empty() {
+        result.to_string()
+    } else {
+        format!("{} > {}", path.join(" > "), result)
+    }
+}
+
+/// Whether the report fails the run, only failures rated `fail_on` or more count when set.
+pub fn is_failure(report: &ComplianceReport, fail_on: Option<Severity>) -> bool {
+    match fail_on {
+        Some(severity) => !report.is_compliant_at(severity),
+        None => !report.is_compliant(),
+    }
+}
+
+/// Whether a CONFIG or POLICY argument is `-`, standing for stdin.
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Read policy variables from a YAML mapping, values must be scalars.
+pub fn read_vars(path: Option<&Path>) -> Result<Variables> {
+    let Some(path) = path else {
+        return Ok(Variables::new());
+    };
+
+    let values: BTreeMap<String, Value> = serde_yaml::from_reader(File::open(path)?)
+        .map_err(|err| anyhow!("{}: {}", path.display(), err))?;
+
+    values
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => {
+                    return Err(anyhow!(
+                        "{}: variable \"{}\" must be a string, a number or a boolean",
+                        path.display(),
+                        name
+                    ))
+                }
+            };
+            Ok((name, value))
+        })
+        .collect()
+}
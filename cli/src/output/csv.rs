@@ -0,0 +1,83 @@
+use std::io::Write;
+
+use anyhow::Result;
+use fcc::compliance::{ComplianceError, ComplianceOk, ComplianceReport};
+
+use super::{policy_file, result_status};
+
+const HEADER: [&str; 9] = [
+    "policy",
+    "configuration",
+    "path",
+    "policy_line",
+    "id",
+    "severity",
+    "status",
+    "found",
+    "message",
+];
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+fn write_record(writer: &mut impl Write, record: &[&str]) -> Result<()> {
+    let record: Vec<String> = record.iter().map(|f| escape(f)).collect();
+    writeln!(writer, "{}", record.join(","))?;
+    Ok(())
+}
+
+pub fn write(writer: &mut impl Write, reports: &[ComplianceReport]) -> Result<()> {
+    write_record(writer, &HEADER)?;
+
+    for report in reports {
+        let policy = report.policy.as_deref().unwrap_or_default();
+        let configuration = report.configuration.as_deref().unwrap_or_default();
+
+        for (path, r) in report.flatten() {
+            let found = match r.result {
+                Ok(ComplianceOk::IsPresent(ref item)) => item.get_item_key(),
+                Err(ComplianceError::ShouldBeAbsentIsPresent(ref item))
+                | Err(ComplianceError::UnexpectedItem(ref item))
+                | Err(ComplianceError::OutOfOrder { ref item, .. })
+                | Err(ComplianceError::ConstraintViolation { ref item, .. }) => item.get_item_key(),
+                _ => "",
+            };
+
+            write_record(
+                writer,
+                &[
+                    policy_file(policy, r),
+                    configuration,
+                    &path.join(" > "),
+                    r.policy.get_item_key(),
+                    r.id.as_deref().unwrap_or_default(),
+                    &r.severity
+                        .map(|s| s.to_string().to_lowercase())
+                        .unwrap_or_default(),
+                    result_status(r),
+                    found,
+                    &r.to_string(),
+                ],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("line 1"), "line 1");
+        assert_eq!(escape("line, 1"), "\"line, 1\"");
+        assert_eq!(escape("line \"1\""), "\"line \"\"1\"\"\"");
+    }
+}
@@ -0,0 +1,10 @@
+use std::io::Write;
+
+use anyhow::Result;
+use fcc::compliance::ComplianceReport;
+
+pub fn write(writer: &mut impl Write, reports: &[ComplianceReport]) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, reports)?;
+    writeln!(writer)?;
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use std::io::Write;
+
+use anyhow::Result;
+use fcc::{
+    compliance::{
+        ComplianceError, ComplianceOk, ComplianceReport, ComplianceSummary, ItemComplianceResult,
+    },
+    config::FlatConfigItem,
+};
+
+use super::policy_file;
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Configuration item a result is about, if any.
+fn found_item(result: &ItemComplianceResult) -> Option<&FlatConfigItem> {
+    match result.result {
+        Ok(ComplianceOk::IsPresent(ref item))
+        | Err(ComplianceError::ShouldBeAbsentIsPresent(ref item))
+        | Err(ComplianceError::UnexpectedItem(ref item))
+        | Err(ComplianceError::OutOfOrder { ref item, .. })
+        | Err(ComplianceError::ConstraintViolation { ref item, .. }) => Some(item),
+        _ => None,
+    }
+}
+
+/// Testcase names of the results, the results of a policy line checked several times
+/// being told apart by the configuration line they found or their bindings.
+fn testcase_names(results: &[(Vec<&str>, &ItemComplianceResult)]) -> Vec<String> {
+    let names: Vec<String> = results
+        .iter()
+        .map(|(path, r)| {
+            let mut testcase = path.clone();
+            testcase.push(r.policy.get_item_key());
+            testcase.join(" > ")
+        })
+        .collect();
+
+    names
+        .iter()
+        .zip(results)
+        .map(|(name, (_, r))| {
+            if names.iter().filter(|n| *n == name).count() == 1 {
+                return name.clone();
+            }
+
+            match found_item(r) {
+                Some(item) => format!(
+                    "{name} (line {}: {})",
+                    item.get_span().line,
+                    item.get_item_key()
+                ),
+                None => {
+                    let bindings: Vec<String> = r
+                        .bindings
+                        .iter()
+                        .map(|(name, value)| format!("{name}=\"{value}\""))
+                        .collect();
+                    format!("{name} ({})", bindings.join(", "))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Group reports by configuration file, keeping the reports order.
+fn group_by_configuration(reports: &[ComplianceReport]) -> Vec<(&str, Vec<&ComplianceReport>)> {
+    let mut suites: Vec<(&str, Vec<&ComplianceReport>)> = vec![];
+    for report in reports {
+        let name = report.configuration.as_deref().unwrap_or("configuration");
+        match suites.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, reports)) => reports.push(report),
+            None => suites.push((name, vec![report])),
+        }
+    }
+    suites
+}
+
+pub fn write(writer: &mut impl Write, reports: &[ComplianceReport]) -> Result<()> {
+    let suites = group_by_configuration(reports);
+
+    let mut total = ComplianceSummary::default();
+    for report in reports {
+        total.add(&report.summary);
+    }
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites name="fcc" tests="{}" failures="{}" skipped="{}">"#,
+        total.total(),
+        total.failed,
+        total.optional + total.not_applicable
+    )?;
+
+    for (name, reports) in suites {
+        let mut summary = ComplianceSummary::default();
+        for report in &reports {
+            summary.add(&report.summary);
+        }
+
+        writeln!(
+            writer,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}">"#,
+            escape(name),
+            summary.total(),
+            summary.failed,
+            summary.optional + summary.not_applicable
+        )?;
+
+        for report in reports {
+            let policy = report.policy.as_deref().unwrap_or("policy");
+            let results: Vec<_> = report.flatten().collect();
+            for (testcase, (_, r)) in testcase_names(&results).iter().zip(&results) {
+                write!(
+                    writer,
+                    r#"    <testcase name="{}" classname="{}""#,
+                    escape(testcase),
+                    escape(policy_file(policy, r))
+                )?;
+
+                match r.result {
+                    Ok(ComplianceOk::OptionalIsAbsent) | Ok(ComplianceOk::NotApplicable) => {
+                        writeln!(writer, ">")?;
+                        writeln!(
+                            writer,
+                            r#"      <skipped message="{}"/>"#,
+                            escape(&r.to_string())
+                        )?;
+                        writeln!(writer, "    </testcase>")?;
+                    }
+                    Ok(_) => {
+                        writeln!(writer, "/>")?;
+                    }
+                    Err(ref err) => {
+                        writeln!(writer, ">")?;
+                        writeln!(
+                            writer,
+                            r#"      <failure message="{}" type="{}"/>"#,
+                            escape(&r.to_string()),
+                            <&str>::from(err)
+                        )?;
+                        writeln!(writer, "    </testcase>")?;
+                    }
+                }
+            }
+        }
+
+        writeln!(writer, "  </testsuite>")?;
+    }
+
+    writeln!(writer, "</testsuites>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use fcc::{compliance::check_compliance, FlatConfig, FlatConfigCompliance};
+
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("line 1"), "line 1");
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_testcase_names() {
+        let policy = FlatConfigCompliance::from_str(
+            "hostname R1\n#[regex]\nntp server \\S+\n#[regex]\ninterface (?<name>\\S+)\n #[state=absent]\n shutdown\n",
+        )
+        .unwrap();
+        let config = FlatConfig::from_str(
+            "hostname R1\nntp server 10.0.0.1\nntp server 10.0.0.2\ninterface Gi0/1\n mtu 1500\ninterface Gi0/2\n mtu 1500\n",
+        )
+        .unwrap();
+        let report = check_compliance(policy, config);
+        let results: Vec<_> = report.flatten().collect();
+
+        assert_eq!(
+            testcase_names(&results),
+            [
+                "hostname R1",
+                r"ntp server \S+ (line 2: ntp server 10.0.0.1)",
+                r"ntp server \S+ (line 3: ntp server 10.0.0.2)",
+                r"interface (?<name>\S+) (line 4: interface Gi0/1)",
+                r"interface Gi0/1 > shutdown",
+                r"interface (?<name>\S+) (line 6: interface Gi0/2)",
+                r"interface Gi0/2 > shutdown",
+            ]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+mod csv;
+mod json;
+mod junit;
+mod sarif;
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use fcc::compliance::{ComplianceOk, ComplianceReport, ItemComplianceResult};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable results, through logs
+    #[default]
+    Text,
+    Json,
+    /// JUnit XML, one testsuite per configuration file
+    Junit,
+    Csv,
+    /// SARIF 2.1, failures annotated on configuration lines
+    Sarif,
+}
+
+#[derive(Args)]
+pub struct OutputArgs {
+    /// Results output format
+    #[arg(short, long, value_enum, default_value_t)]
+    pub output: OutputFormat,
+
+    /// Write results to a file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    pub output_file: Option<PathBuf>,
+}
+
+impl OutputArgs {
+    pub fn is_text(&self) -> bool {
+        matches!(self.output, OutputFormat::Text)
+    }
+
+    pub fn write(&self, reports: &[ComplianceReport]) -> Result<()> {
+        let mut writer: Box<dyn Write> = match self.output_file {
+            Some(ref path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(io::stdout().lock()),
+        };
+
+        match self.output {
+            OutputFormat::Text => {}
+            OutputFormat::Json => json::write(&mut writer, reports)?,
+            OutputFormat::Junit => junit::write(&mut writer, reports)?,
+            OutputFormat::Csv => csv::write(&mut writer, reports)?,
+            OutputFormat::Sarif => sarif::write(&mut writer, reports)?,
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Policy file of the result, the included one for items of included files.
+fn policy_file<'a>(policy: &'a str, result: &'a ItemComplianceResult) -> &'a str {
+    result.policy.get_file().unwrap_or(policy)
+}
+
+fn result_status(result: &ItemComplianceResult) -> &'static str {
+    match result.result {
+        Ok(ComplianceOk::OptionalIsAbsent) => "optional",
+        Ok(ComplianceOk::NotApplicable) => "not-applicable",
+        Ok(_) => "passed",
+        Err(_) => "failed",
+    }
+}
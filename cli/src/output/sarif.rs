@@ -0,0 +1,155 @@
+use std::io::Write;
+
+use anyhow::Result;
+use fcc::{
+    compliance::{
+        ComplianceError, ComplianceOk, ComplianceReport, ComplianceReportNode,
+        ItemComplianceResult, Severity,
+    },
+    config::FlatConfigItem,
+};
+use serde_json::{json, Value};
+
+use super::policy_file;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Default)]
+struct SarifRun {
+    rules: Vec<Value>,
+    rule_ids: Vec<String>,
+    results: Vec<Value>,
+}
+
+impl SarifRun {
+    /// Index of the rule built from the policy item, created on first use.
+    fn rule_index(&mut self, policy_source: &str, result: &ItemComplianceResult) -> usize {
+        let policy = &result.policy;
+        let policy_source = policy_file(policy_source, result);
+        let id = match result.id {
+            Some(ref id) => id.clone(),
+            None => format!("{}:{}", policy_source, policy.get_span().line),
+        };
+        if let Some(index) = self.rule_ids.iter().position(|r| *r == id) {
+            return index;
+        }
+
+        let mut rule = json!({
+            "id": id,
+            "name": policy.get_item_key(),
+            "shortDescription": { "text": policy.get_item_key() },
+            "defaultConfiguration": { "level": level(result.severity) },
+            "properties": {
+                "source": policy_source,
+                "line": policy.get_span().line,
+            },
+        });
+        if let Some(ref description) = result.description {
+            rule["fullDescription"] = json!({ "text": description });
+        }
+        if let Some(severity) = result.severity {
+            rule["properties"]["severity"] = json!(severity.to_string().to_lowercase());
+        }
+        if !result.tags.is_empty() {
+            rule["properties"]["tags"] = json!(result.tags);
+        }
+        self.rules.push(rule);
+        self.rule_ids.push(id);
+        self.rule_ids.len() - 1
+    }
+
+    fn add_nodes(
+        &mut self,
+        report: &ComplianceReport,
+        nodes: &[ComplianceReportNode],
+        block: Option<&FlatConfigItem>,
+    ) {
+        let policy_source = report.policy.as_deref().unwrap_or("policy");
+        let config_source = report.configuration.as_deref().unwrap_or("configuration");
+
+        for node in nodes {
+            let r = &node.result;
+            match r.result {
+                Ok(ComplianceOk::IsPresent(ref item)) => {
+                    self.add_nodes(report, &node.children, Some(item));
+                }
+                Ok(_) => {}
+                Err(ref err) => {
+                    // Missing items are reported on their parent block, if any
+                    let item = match err {
+                        ComplianceError::ShouldBeAbsentIsPresent(ref item)
+                        | ComplianceError::UnexpectedItem(ref item)
+                        | ComplianceError::OutOfOrder { ref item, .. }
+                        | ComplianceError::ConstraintViolation { ref item, .. } => Some(item),
+                        ComplianceError::CountMismatch { ref found, .. } => found.first().or(block),
+                        ComplianceError::ShouldBePresentIsAbsent
+                        | ComplianceError::InvalidPattern { .. } => block,
+                    };
+
+                    let mut physical_location = json!({
+                        "artifactLocation": { "uri": config_source },
+                    });
+                    if let Some(item) = item {
+                        physical_location["region"] = json!({ "startLine": item.get_span().line });
+                    }
+
+                    let rule_index = self.rule_index(policy_source, r);
+                    self.results.push(json!({
+                        "ruleId": self.rule_ids[rule_index],
+                        "ruleIndex": rule_index,
+                        "level": level(r.severity),
+                        "message": { "text": r.to_string() },
+                        "locations": [{ "physicalLocation": physical_location }],
+                        "relatedLocations": [{
+                            "id": 0,
+                            "message": { "text": "policy" },
+                            "physicalLocation": {
+                                "artifactLocation": { "uri": policy_file(policy_source, r) },
+                                "region": { "startLine": r.policy.get_span().line },
+                            },
+                        }],
+                    }));
+
+                    // Items out of order are still checked inside
+                    self.add_nodes(report, &node.children, item);
+                }
+            }
+        }
+    }
+}
+
+/// SARIF level of a failure, unrated failures are errors.
+fn level(severity: Option<Severity>) -> &'static str {
+    match severity {
+        None | Some(Severity::Critical) | Some(Severity::High) => "error",
+        Some(Severity::Medium) => "warning",
+        Some(Severity::Low) | Some(Severity::Info) => "note",
+    }
+}
+
+pub fn write(writer: &mut impl Write, reports: &[ComplianceReport]) -> Result<()> {
+    let mut run = SarifRun::default();
+    for report in reports {
+        run.add_nodes(report, &report.results, None);
+    }
+
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_BIN_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": run.rules,
+                },
+            },
+            "results": run.results,
+        }],
+    });
+
+    serde_json::to_writer_pretty(&mut *writer, &sarif)?;
+    writeln!(writer)?;
+    Ok(())
+}
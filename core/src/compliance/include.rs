@@ -0,0 +1,283 @@
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::FlatConfigItem,
+    error::{ComplianceOptionParseError, FlatConfigError, ParseError},
+    parse::{misc::ParseOption, parse_configuration},
+};
+
+use super::{options::syntax::parse_option_line, FlatConfigCompliance};
+
+/// Option line standing for policy items instead of applying to the next one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Directive {
+    /// `#[include=path]`, items of another policy file, relative to the including one.
+    Include(String),
+    /// `#[use=name]`, items of a snippet defined before.
+    Use(String),
+    /// `#[define=name]`, snippet made of the indented items below.
+    Define(String),
+}
+
+/// Directive of an option line, a directive being the only option of its line.
+pub(crate) fn directive(key: &str) -> Option<Directive> {
+    let mut options = parse_option_line(key).ok()?;
+    if options.len() != 1 {
+        return None;
+    }
+
+    let option = options.pop()?;
+    let arg = option.arg?;
+    match option.name.as_str() {
+        "include" => Some(Directive::Include(arg)),
+        "use" => Some(Directive::Use(arg)),
+        "define" => Some(Directive::Define(arg)),
+        _ => None,
+    }
+}
+
+/// Expands the directives of policies, reading included files with the same parse options.
+pub(crate) struct PolicyLoader {
+    options: ParseOption,
+    /// Canonical paths of the files being loaded, outermost first.
+    files: Vec<PathBuf>,
+    snippets: BTreeMap<String, Vec<FlatConfigItem>>,
+}
+
+impl PolicyLoader {
+    pub(crate) fn new(options: ParseOption) -> Self {
+        Self {
+            options,
+            files: vec![],
+            snippets: BTreeMap::new(),
+        }
+    }
+
+    /// Read and expand a policy file, included files being relative to its directory.
+    pub(crate) fn load_file(
+        &mut self,
+        path: &Path,
+    ) -> Result<Vec<FlatConfigItem>, FlatConfigError> {
+        let file = path.canonicalize().map_err(FlatConfigError::IO)?;
+        if self.files.contains(&file) {
+            let mut chain: Vec<String> =
+                self.files.iter().map(|f| f.display().to_string()).collect();
+            chain.push(file.display().to_string());
+            return Err(FlatConfigError::IncludeCycle(chain));
+        }
+
+        let raw = read_to_string(&file).map_err(FlatConfigError::IO)?;
+        let mut items = self.parse(&raw)?;
+        // Lines of included items, snippets included, are the ones of their own file
+        if !self.files.is_empty() {
+            let included = path.display().to_string();
+            items.iter_mut().for_each(|item| item.set_file(&included));
+        }
+
+        self.files.push(file);
+        let result = self.expand(items, path.parent());
+        self.files.pop();
+        result
+    }
+
+    /// Parse and expand a policy, includes being refused without a directory to resolve them.
+    pub(crate) fn load_str(
+        &mut self,
+        raw: &str,
+        dir: Option<&Path>,
+    ) -> Result<Vec<FlatConfigItem>, FlatConfigError> {
+        let items = self.parse(raw)?;
+        self.expand(items, dir)
+    }
+
+    fn parse(&self, raw: &str) -> Result<Vec<FlatConfigItem>, FlatConfigError> {
+        let options = ParseOption {
+            ignore_options: false,
+            ..self.options.clone()
+        };
+        let policy: FlatConfigCompliance = parse_configuration(raw, Some(options))?;
+        Ok(policy.items)
+    }
+
+    fn expand(
+        &mut self,
+        items: Vec<FlatConfigItem>,
+        dir: Option<&Path>,
+    ) -> Result<Vec<FlatConfigItem>, FlatConfigError> {
+        let mut expanded = vec![];
+
+        for item in items {
+            let position = item.get_position();
+            let error = |err| FlatConfigError::Parse(ParseError::ComplianceOption(err, position));
+
+            match (directive(item.get_item_key()), item) {
+                (Some(Directive::Include(path)), _) => {
+                    let Some(dir) = dir else {
+                        return Err(error(ComplianceOptionParseError::UnresolvedInclude(path)));
+                    };
+                    let mut items = self.load_file(&dir.join(&path)).map_err(|err| {
+                        FlatConfigError::Include {
+                            path: path.clone(),
+                            line: position.line,
+                            error: Box::new(err),
+                        }
+                    })?;
+                    expanded.append(&mut items);
+                }
+                (Some(Directive::Use(name)), _) => match self.snippets.get(&name) {
+                    Some(items) => expanded.extend(items.iter().cloned()),
+                    None => return Err(error(ComplianceOptionParseError::UnknownSnippet(name))),
+                },
+                (Some(Directive::Define(name)), FlatConfigItem::Parent(parent)) => {
+                    if self.snippets.contains_key(&name) {
+                        return Err(error(ComplianceOptionParseError::DuplicatedSnippet(name)));
+                    }
+                    let items = self.expand(parent.items, dir)?;
+                    self.snippets.insert(name, items);
+                }
+                (_, FlatConfigItem::Parent(mut parent)) => {
+                    parent.items = self.expand(parent.items, dir)?;
+                    expanded.push(FlatConfigItem::Parent(parent));
+                }
+                (_, item) => expanded.push(item),
+            }
+        }
+
+        Ok(expanded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_directive() {
+        assert_eq!(
+            directive("#[include=common/aaa.txt]"),
+            Some(Directive::Include(String::from("common/aaa.txt")))
+        );
+        assert_eq!(
+            directive(r#" #[use="ntp servers"] "#),
+            Some(Directive::Use(String::from("ntp servers")))
+        );
+        assert_eq!(directive("#[include]"), None);
+        assert_eq!(directive("#[include=aaa.txt, regex]"), None);
+        assert_eq!(directive("#[regex]"), None);
+    }
+
+    #[test]
+    fn test_load_file() {
+        let policy = FlatConfigCompliance::from_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test/include/1_p.txt"
+        ))
+        .unwrap();
+
+        let keys: Vec<&str> = policy.items.iter().map(|i| i.get_item_key()).collect();
+        assert_eq!(
+            keys,
+            [
+                "hostname router",
+                "aaa new-model",
+                "aaa authentication login default local",
+                "ntp server 10.0.0.1",
+                "ntp server 10.0.0.2",
+                "line vty 0 4",
+            ]
+        );
+        // Included items keep the position in their own file
+        assert_eq!(policy.items[2].get_span().line, 5);
+        let files: Vec<Option<&str>> = policy.items.iter().map(|i| i.get_file()).collect();
+        assert_eq!(files[0], None);
+        assert!(files[1].is_some_and(|f| f.ends_with("common/aaa.txt")));
+        assert!(files[2].is_some_and(|f| f.ends_with("common/login.txt")));
+        let FlatConfigItem::Parent(ref vty) = policy.items[5] else {
+            panic!()
+        };
+        assert_eq!(vty.items.len(), 2);
+        // Snippets keep the file they are defined in
+        assert!(vty.items[0]
+            .get_file()
+            .is_some_and(|f| f.ends_with("common/login.txt")));
+        assert!(policy.source.unwrap().ends_with("1_p.txt"));
+    }
+
+    #[test]
+    fn test_load_file_errors() {
+        let err = FlatConfigCompliance::from_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test/include/2_p.txt"
+        ))
+        .unwrap_err();
+        let FlatConfigError::Include {
+            ref path,
+            line,
+            ref error,
+        } = err
+        else {
+            panic!()
+        };
+        assert_eq!((path.as_str(), line), ("3_p.txt", 2));
+        let FlatConfigError::Include { ref error, .. } = **error else {
+            panic!()
+        };
+        let FlatConfigError::IncludeCycle(ref chain) = **error else {
+            panic!()
+        };
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain[0], chain[2]);
+
+        let err = FlatConfigCompliance::from_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test/include/4_p.txt"
+        ))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "in \"common/invalid.txt\" included line 3: Flat configuration parsing error: line 2, column 9: Unable to parse option argument \"unknown\" from \"state\""
+        );
+    }
+
+    #[test]
+    fn test_load_str_snippets() {
+        let policy =
+            FlatConfigCompliance::from_str("#[define=ntp]\n ntp server 10.0.0.1\n#[use=ntp]\n")
+                .unwrap();
+        assert_eq!(policy.items.len(), 1);
+
+        let err = FlatConfigCompliance::from_str("#[use=ntp]\n").unwrap_err();
+        assert!(matches!(
+            err,
+            FlatConfigError::Parse(ParseError::ComplianceOption(
+                ComplianceOptionParseError::UnknownSnippet(_),
+                _
+            ))
+        ));
+
+        let err = FlatConfigCompliance::from_str("#[include=aaa.txt]\n").unwrap_err();
+        assert!(matches!(
+            err,
+            FlatConfigError::Parse(ParseError::ComplianceOption(
+                ComplianceOptionParseError::UnresolvedInclude(_),
+                _
+            ))
+        ));
+
+        let err =
+            FlatConfigCompliance::from_str("#[regex]\n#[include=aaa.txt]\nline\n").unwrap_err();
+        assert!(matches!(
+            err,
+            FlatConfigError::Parse(ParseError::ComplianceOption(
+                ComplianceOptionParseError::MisplacedDirective(_),
+                _
+            ))
+        ));
+    }
+}
@@ -0,0 +1,377 @@
+use std::borrow::Cow;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::parse::misc::normalize_whitespace;
+
+use super::{
+    options::{ComplianceOptions, MatchKind},
+    typed::{typed_placeholders, TypedPlaceholder},
+    variables::{captured_values, escape_placeholders},
+    Variables,
+};
+
+/// Prefix of the regex groups matching the typed placeholders, by position.
+const TYPED_GROUP: &str = "__typed";
+
+/// Regex of a policy key, nothing for a key compared as is. Typed placeholders match
+/// one word.
+pub(crate) fn key_pattern(key: &str, kind: MatchKind) -> Option<String> {
+    let typed = typed_placeholders(key).unwrap_or_default();
+    if matches!(kind, MatchKind::Exact) && typed.is_empty() {
+        return None;
+    }
+
+    let literal = |part: &str| match kind {
+        MatchKind::Regex => escape_placeholders(part).into_owned(),
+        MatchKind::Glob => glob_to_regex(part),
+        _ => regex::escape(part),
+    };
+    let mut pattern = String::with_capacity(key.len());
+    let mut last = 0;
+    for (i, (range, _)) in typed.iter().enumerate() {
+        pattern.push_str(&literal(&key[last..range.start]));
+        pattern.push_str(&format!(r"(?<{TYPED_GROUP}{i}>\S+)"));
+        last = range.end;
+    }
+    pattern.push_str(&literal(&key[last..]));
+
+    let pattern = match kind {
+        MatchKind::Prefix => format!("{pattern}.*"),
+        MatchKind::Suffix => format!(".*{pattern}"),
+        MatchKind::Contains => format!(".*{pattern}.*"),
+        _ => pattern,
+    };
+    Some(format!("(?s)^{}$", pattern))
+}
+
+/// Translate `*`, `?` and `[...]` (negated with `!`), other characters match themselves.
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::with_capacity(glob.len());
+    let mut class = false;
+    for (i, c) in glob.char_indices() {
+        match c {
+            ']' if class => {
+                class = false;
+                pattern.push(c);
+            }
+            '!' if class && glob[..i].ends_with('[') => pattern.push('^'),
+            '\\' | '^' | '[' if class => {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+            _ if class => pattern.push(c),
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '[' => {
+                class = true;
+                pattern.push(c);
+            }
+            _ => pattern.push_str(&regex::escape(&String::from(c))),
+        }
+    }
+    pattern
+}
+
+/// Text matching itself in a key of the given kind.
+pub(crate) fn escape_literal(text: &str, kind: MatchKind) -> Cow<'_, str> {
+    match kind {
+        MatchKind::Regex => Cow::Owned(regex::escape(text)),
+        MatchKind::Glob if text.contains(['*', '?', '[']) => {
+            let mut escaped = String::with_capacity(text.len());
+            for c in text.chars() {
+                match c {
+                    '*' | '?' | '[' => {
+                        escaped.push('[');
+                        escaped.push(c);
+                        escaped.push(']');
+                    }
+                    _ => escaped.push(c),
+                }
+            }
+            Cow::Owned(escaped)
+        }
+        // Literal parts of the other kinds are escaped when building the pattern
+        _ => Cow::Borrowed(text),
+    }
+}
+
+/// Result of the comparison of a configuration key with a policy key.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum KeyCheck {
+    Match,
+    NoMatch,
+    /// The key matches but a word does not satisfy its typed placeholder.
+    Violation {
+        value: String,
+        constraint: String,
+    },
+}
+
+/// Match configuration item keys with a policy key or pattern, in the match modes of
+/// a policy item.
+pub(crate) struct KeyMatcher {
+    regex: Option<Regex>,
+    key: String,
+    typed: Vec<TypedPlaceholder>,
+    ignore_case: bool,
+    normalize_whitespace: bool,
+}
+
+impl KeyMatcher {
+    /// Patterns are checked when parsing the policy, but variables and bindings replaced
+    /// afterwards can still make them invalid.
+    pub(crate) fn new(
+        key: &str,
+        kind: MatchKind,
+        options: &ComplianceOptions,
+    ) -> Result<Self, regex::Error> {
+        let regex = key_pattern(key, kind)
+            .map(|pattern| {
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(options.ignore_case)
+                    .build()
+            })
+            .transpose()?;
+        let typed = typed_placeholders(key)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, typed)| typed)
+            .collect();
+        let key = if options.normalize_whitespace {
+            normalize_whitespace(key)
+        } else {
+            String::from(key)
+        };
+
+        Ok(Self {
+            regex,
+            key,
+            typed,
+            ignore_case: options.ignore_case,
+            normalize_whitespace: options.normalize_whitespace,
+        })
+    }
+
+    fn normalize<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        if self.normalize_whitespace {
+            Cow::Owned(normalize_whitespace(key))
+        } else {
+            Cow::Borrowed(key)
+        }
+    }
+
+    pub(crate) fn check(&self, key: &str) -> KeyCheck {
+        let key = self.normalize(key);
+        let is_match = match self.regex {
+            Some(ref regex) if !self.typed.is_empty() => {
+                let Some(caps) = regex.captures(&key) else {
+                    return KeyCheck::NoMatch;
+                };
+                for (i, typed) in self.typed.iter().enumerate() {
+                    // A placeholder in an optional group that did not match has no value
+                    let Some(value) = caps.name(&format!("{TYPED_GROUP}{i}")) else {
+                        continue;
+                    };
+                    let value = value.as_str();
+                    if !typed.check(value) {
+                        return KeyCheck::Violation {
+                            value: String::from(value),
+                            constraint: typed.to_string(),
+                        };
+                    }
+                }
+                true
+            }
+            Some(ref regex) => regex.is_match(&key),
+            None if self.ignore_case => self.key.to_lowercase() == key.to_lowercase(),
+            None => self.key == key,
+        };
+
+        if is_match {
+            KeyCheck::Match
+        } else {
+            KeyCheck::NoMatch
+        }
+    }
+
+    pub(crate) fn is_match(&self, key: &str) -> bool {
+        self.check(key) == KeyCheck::Match
+    }
+
+    /// Values of the regex named groups and of the named typed placeholders.
+    pub(crate) fn captured_values(&self, key: &str) -> Variables {
+        let Some(ref regex) = self.regex else {
+            return Variables::new();
+        };
+        let key = self.normalize(key);
+
+        let mut values = captured_values(regex, &key);
+        values.retain(|name, _| !name.starts_with(TYPED_GROUP));
+        if let Some(caps) = regex.captures(&key) {
+            for (i, typed) in self.typed.iter().enumerate() {
+                let value = caps.name(&format!("{TYPED_GROUP}{i}"));
+                if let (Some(name), Some(value)) = (&typed.name, value) {
+                    values.insert(name.clone(), String::from(value.as_str()));
+                }
+            }
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(ignore_case: bool, normalize_whitespace: bool) -> ComplianceOptions {
+        ComplianceOptions {
+            ignore_case,
+            normalize_whitespace,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_key_matcher() {
+        let matcher = KeyMatcher::new(
+            "ip address 10.0.0.1",
+            MatchKind::Exact,
+            &options(false, false),
+        )
+        .unwrap();
+        assert!(matcher.is_match("ip address 10.0.0.1"));
+        assert!(!matcher.is_match("IP address 10.0.0.1"));
+        assert!(!matcher.is_match("ip  address 10.0.0.1"));
+
+        let matcher = KeyMatcher::new(
+            "ip address 10.0.0.1",
+            MatchKind::Exact,
+            &options(true, true),
+        )
+        .unwrap();
+        assert!(matcher.is_match("IP  Address\t10.0.0.1 "));
+
+        let matcher = KeyMatcher::new(
+            r"ip address (?<ip>\S+)",
+            MatchKind::Regex,
+            &options(true, true),
+        )
+        .unwrap();
+        assert!(matcher.is_match("IP  address 10.0.0.1"));
+        assert_eq!(
+            matcher.captured_values("IP  address 10.0.0.1")["ip"],
+            "10.0.0.1"
+        );
+    }
+
+    #[test]
+    fn test_key_matcher_kinds() {
+        let options = options(false, false);
+        let matcher =
+            KeyMatcher::new("snmp-server community", MatchKind::Prefix, &options).unwrap();
+        assert!(matcher.is_match("snmp-server community s3cr.t RO"));
+        assert!(!matcher.is_match("no snmp-server community s3cr.t RO"));
+
+        let matcher = KeyMatcher::new("(DC1)", MatchKind::Suffix, &options).unwrap();
+        assert!(matcher.is_match("description Paris (DC1)"));
+
+        let matcher = KeyMatcher::new("10.0.0.", MatchKind::Contains, &options).unwrap();
+        assert!(matcher.is_match("ntp server 10.0.0.1 prefer"));
+        assert!(!matcher.is_match("ntp server 10.0.001"));
+
+        let matcher =
+            KeyMatcher::new("snmp-server community * R[!W]", MatchKind::Glob, &options).unwrap();
+        assert!(matcher.is_match("snmp-server community s3cr.t RO"));
+        assert!(!matcher.is_match("snmp-server community s3cr.t RW"));
+
+        let matcher = KeyMatcher::new("interface Gi0/? ", MatchKind::Glob, &options).unwrap();
+        assert!(matcher.is_match("interface Gi0/1 "));
+        assert!(!matcher.is_match("interface Gi0/10 "));
+    }
+
+    #[test]
+    fn test_escape_literal() {
+        let options = options(false, false);
+        for kind in [
+            MatchKind::Exact,
+            MatchKind::Regex,
+            MatchKind::Prefix,
+            MatchKind::Suffix,
+            MatchKind::Contains,
+            MatchKind::Glob,
+        ] {
+            let key = format!("description {}", escape_literal("[a*b?] (c)", kind));
+            let matcher = KeyMatcher::new(&key, kind, &options).unwrap();
+            assert!(matcher.is_match("description [a*b?] (c)"), "{kind:?}");
+            assert!(!matcher.is_match("description [axxb?] (c)"), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn test_key_matcher_invalid_pattern() {
+        assert!(
+            KeyMatcher::new("interface Gi0/[1-", MatchKind::Glob, &options(false, false)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_key_pattern_invalid_glob() {
+        let pattern = key_pattern("interface Gi0/[1-", MatchKind::Glob).unwrap();
+        assert!(Regex::new(&pattern).is_err());
+    }
+
+    #[test]
+    fn test_key_matcher_typed_placeholders() {
+        let options = options(false, false);
+        let matcher = KeyMatcher::new(
+            "ip route 0.0.0.0 0.0.0.0 {{ :ipv4 in 10.0.0.0/8 }}",
+            MatchKind::Exact,
+            &options,
+        )
+        .unwrap();
+        assert!(matcher.is_match("ip route 0.0.0.0 0.0.0.0 10.1.1.1"));
+        assert_eq!(
+            matcher.check("ip route 10.0.0.0 255.0.0.0 10.1.1.1"),
+            KeyCheck::NoMatch
+        );
+        assert_eq!(
+            matcher.check("ip route 0.0.0.0 0.0.0.0 192.0.2.1"),
+            KeyCheck::Violation {
+                value: String::from("192.0.2.1"),
+                constraint: String::from("ipv4 in 10.0.0.0/8")
+            }
+        );
+
+        let matcher = KeyMatcher::new(
+            r"logging host {{ host:ip }}( vrf \S+)?",
+            MatchKind::Regex,
+            &options,
+        )
+        .unwrap();
+        assert!(matcher.is_match("logging host 10.0.0.1 vrf MGMT"));
+        assert_eq!(
+            matcher.captured_values("logging host 10.0.0.1 vrf MGMT"),
+            Variables::from([(String::from("host"), String::from("10.0.0.1"))])
+        );
+    }
+
+    #[test]
+    fn test_key_matcher_optional_typed_placeholders() {
+        let matcher = KeyMatcher::new(
+            "logging host( {{ host:ip }})?",
+            MatchKind::Regex,
+            &options(false, false),
+        )
+        .unwrap();
+        assert_eq!(matcher.check("logging host"), KeyCheck::Match);
+        assert_eq!(matcher.captured_values("logging host"), Variables::new());
+        assert_eq!(
+            matcher.check("logging host server"),
+            KeyCheck::Violation {
+                value: String::from("server"),
+                constraint: String::from("ip")
+            }
+        );
+    }
+}
@@ -1,50 +1,138 @@
 use std::{error, fmt};
 
+use strum::IntoStaticStr;
+
 use crate::config::FlatConfigItem;
 
-use super::options::{ComplianceOptionsContainer, MatchOption, StateOption};
+use super::{
+    options::{
+        ComplianceOptionsContainer, CountOption, MatchKind, MatchOption, ScopeOption, Severity,
+        StateOption,
+    },
+    Variables,
+};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
 pub struct ItemComplianceResult {
     pub policy: FlatConfigItem,
     pub result: Result<ComplianceOk, ComplianceError>,
+    /// Values bound to the regex named groups when the item was checked.
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")
+    )]
+    pub bindings: Variables,
+    /// Keys of the blocks above the found item, for items searched in descendants.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub path: Vec<String>,
+    /// Rule identifier of the policy item.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub id: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub severity: Option<Severity>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub description: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub tags: Vec<String>,
 }
 
 impl ItemComplianceResult {
-    pub fn new_present_ok(policy: FlatConfigItem, item: FlatConfigItem) -> Self {
+    fn new(policy: FlatConfigItem, result: Result<ComplianceOk, ComplianceError>) -> Self {
+        let options = policy.get_options();
         Self {
             policy,
-            result: Ok(ComplianceOk::IsPresent(item)),
+            result,
+            bindings: Variables::new(),
+            path: vec![],
+            id: options.id,
+            severity: options.severity,
+            description: options.description,
+            tags: options.tags,
         }
     }
 
+    pub fn new_present_ok(policy: FlatConfigItem, item: FlatConfigItem) -> Self {
+        Self::new(policy, Ok(ComplianceOk::IsPresent(item)))
+    }
+
     pub fn new_absent_ok(policy: FlatConfigItem) -> Self {
-        Self {
-            policy,
-            result: Ok(ComplianceOk::IsAbsent),
-        }
+        Self::new(policy, Ok(ComplianceOk::IsAbsent))
     }
 
     pub fn new_present_nok_ok(policy: FlatConfigItem) -> Self {
-        Self {
-            policy,
-            result: Ok(ComplianceOk::OptionalIsAbsent),
-        }
+        Self::new(policy, Ok(ComplianceOk::OptionalIsAbsent))
+    }
+
+    pub fn new_not_applicable(policy: FlatConfigItem) -> Self {
+        Self::new(policy, Ok(ComplianceOk::NotApplicable))
     }
 
     pub fn new_absent_nok(policy: FlatConfigItem, item: FlatConfigItem) -> Self {
-        Self {
-            policy,
-            result: Err(ComplianceError::ShouldBeAbsentIsPresent(item)),
-        }
+        Self::new(policy, Err(ComplianceError::ShouldBeAbsentIsPresent(item)))
     }
 
     pub fn new_present_nok(policy: FlatConfigItem) -> Self {
-        Self {
+        Self::new(policy, Err(ComplianceError::ShouldBePresentIsAbsent))
+    }
+
+    pub fn new_out_of_order(
+        policy: FlatConfigItem,
+        item: FlatConfigItem,
+        expected: usize,
+        found: usize,
+    ) -> Self {
+        Self::new(
             policy,
-            result: Err(ComplianceError::ShouldBePresentIsAbsent),
-        }
+            Err(ComplianceError::OutOfOrder {
+                item,
+                expected,
+                found,
+            }),
+        )
+    }
+
+    pub fn new_count_mismatch(
+        policy: FlatConfigItem,
+        expected: CountOption,
+        found: Vec<FlatConfigItem>,
+    ) -> Self {
+        Self::new(
+            policy,
+            Err(ComplianceError::CountMismatch { expected, found }),
+        )
+    }
+
+    pub fn with_bindings(mut self, bindings: Variables) -> Self {
+        self.bindings = bindings;
+        self
+    }
+
+    pub fn new_constraint_violation(
+        policy: FlatConfigItem,
+        item: FlatConfigItem,
+        value: String,
+        constraint: String,
+    ) -> Self {
+        Self::new(
+            policy,
+            Err(ComplianceError::ConstraintViolation {
+                item,
+                value,
+                constraint,
+            }),
+        )
+    }
+
+    pub fn new_unexpected(policy: FlatConfigItem, item: FlatConfigItem) -> Self {
+        Self::new(policy, Err(ComplianceError::UnexpectedItem(item)))
+    }
+
+    pub fn new_invalid_pattern(policy: FlatConfigItem, pattern: String, reason: String) -> Self {
+        Self::new(
+            policy,
+            Err(ComplianceError::InvalidPattern { pattern, reason }),
+        )
     }
 }
 
@@ -53,6 +141,14 @@ impl fmt::Display for ItemComplianceResult {
         let options = self.policy.get_options();
         write!(f, "Policy(")?;
 
+        if let Some(ref id) = self.id {
+            write!(f, "id={id},")?;
+        }
+
+        if let Some(severity) = self.severity {
+            write!(f, "severity={severity},")?;
+        }
+
         if options.state != StateOption::default() {
             write!(f, "state: {},", options.state)?;
         }
@@ -61,6 +157,46 @@ impl fmt::Display for ItemComplianceResult {
             write!(f, "regex=true,")?;
         }
 
+        if !matches!(options.match_kind, MatchKind::Exact | MatchKind::Regex) {
+            write!(f, "match-kind={},", options.match_kind)?;
+        }
+
+        if options.scope != ScopeOption::default() {
+            write!(f, "scope={},", options.scope)?;
+        }
+
+        if options.ignore_case {
+            write!(f, "ignore-case=true,")?;
+        }
+
+        if options.normalize_whitespace {
+            write!(f, "normalize-whitespace=true,")?;
+        }
+
+        if options.exact {
+            write!(f, "exact=true,")?;
+        }
+
+        if let Some(order) = options.order {
+            write!(f, "order={},", order)?;
+        }
+
+        if let Some(count) = options.count {
+            write!(f, "count={},", count)?;
+        }
+
+        if let Some(for_each) = options.for_each {
+            write!(f, "for-each={},", for_each)?;
+        }
+
+        if let Some(ref having) = options.having {
+            write!(f, "having=\"{}\",", having)?;
+        }
+
+        if let Some(ref when) = options.when {
+            write!(f, "when=\"{}\",", when)?;
+        }
+
         if options.r#match != MatchOption::default() {
             write!(f, "match={}", options.r#match)?;
         }
@@ -78,6 +214,9 @@ impl fmt::Display for ItemComplianceResult {
                 ComplianceOk::IsAbsent => {
                     write!(f, "nothing found, as it should be.")?;
                 }
+                ComplianceOk::NotApplicable => {
+                    write!(f, "condition not met, not applicable.")?;
+                }
             },
             Err(err) => match err {
                 ComplianceError::ShouldBePresentIsAbsent => {
@@ -90,18 +229,80 @@ impl fmt::Display for ItemComplianceResult {
                         item.get_item_key()
                     )?;
                 }
+                ComplianceError::OutOfOrder {
+                    ref item,
+                    expected,
+                    found,
+                } => {
+                    write!(
+                        f,
+                        "found \"{}\" at position {found}, expected position {expected}.",
+                        item.get_item_key()
+                    )?;
+                }
+                ComplianceError::CountMismatch {
+                    ref expected,
+                    ref found,
+                } => {
+                    write!(
+                        f,
+                        "found {} match(es), expected {expected}: {:?}",
+                        found.len(),
+                        found.iter().map(|i| i.get_item_key()).collect::<Vec<_>>()
+                    )?;
+                }
+                ComplianceError::ConstraintViolation {
+                    ref item,
+                    ref value,
+                    ref constraint,
+                } => {
+                    write!(
+                        f,
+                        "found \"{}\" but \"{value}\" is not {constraint}.",
+                        item.get_item_key()
+                    )?;
+                }
+                ComplianceError::UnexpectedItem(ref item) => {
+                    write!(
+                        f,
+                        "found something the policy does not list: \"{}\"",
+                        item.get_item_key()
+                    )?;
+                }
+                ComplianceError::InvalidPattern {
+                    ref pattern,
+                    ref reason,
+                } => {
+                    write!(f, "invalid pattern \"{pattern}\": {reason}")?;
+                }
             },
         };
+
+        if !self.path.is_empty() {
+            write!(f, " under \"{}\"", self.path.join(" > "))?;
+        }
+
+        if !self.bindings.is_empty() {
+            let bindings: Vec<String> = self
+                .bindings
+                .iter()
+                .map(|(name, value)| format!("{name}=\"{value}\""))
+                .collect();
+            write!(f, " with {}", bindings.join(", "))?;
+        }
         Ok(())
     }
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, IntoStaticStr)]
+#[allow(clippy::large_enum_variant)]
 pub enum ComplianceOk {
     IsPresent(FlatConfigItem),
     OptionalIsAbsent,
     IsAbsent,
+    /// No configuration item of the same level matches the `when` condition.
+    NotApplicable,
 }
 
 impl fmt::Display for ComplianceOk {
@@ -111,10 +312,34 @@ impl fmt::Display for ComplianceOk {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, IntoStaticStr)]
 pub enum ComplianceError {
     ShouldBePresentIsAbsent,
     ShouldBeAbsentIsPresent(FlatConfigItem),
+    /// Configuration item of an exact block not matched by any policy item.
+    UnexpectedItem(FlatConfigItem),
+    /// Item found out of the order required by the parent policy, positions start at 1.
+    OutOfOrder {
+        item: FlatConfigItem,
+        expected: usize,
+        found: usize,
+    },
+    /// Item matching the policy except a word not satisfying its typed placeholder.
+    ConstraintViolation {
+        item: FlatConfigItem,
+        value: String,
+        constraint: String,
+    },
+    /// Number of matches out of the count options bounds, with the matched items.
+    CountMismatch {
+        expected: CountOption,
+        found: Vec<FlatConfigItem>,
+    },
+    /// Pattern left invalid once variables are replaced, with the regex error.
+    InvalidPattern {
+        pattern: String,
+        reason: String,
+    },
 }
 
 impl error::Error for ComplianceError {}
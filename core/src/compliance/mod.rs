@@ -1,129 +1,473 @@
-use std::{collections::BTreeSet, io, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::Path,
+    str::FromStr,
+};
 
-use options::{ComplianceOptionsContainer, MatchOption, StateOption};
-use regex::Regex;
+use include::PolicyLoader;
+use matcher::{KeyCheck, KeyMatcher};
+use options::{
+    BindOption, ComplianceOptionsContainer, ForEachOption, MatchKind, MatchOption, OrderOption,
+    ScopeOption, StateOption,
+};
 
 use crate::{
-    config::{FlatConfig, FlatConfigItem},
+    config::{FlatConfig, FlatConfigItem, FlatConfigParent},
     error::FlatConfigError,
-    parse::{parse_configuration, ItemsContainer},
+    parse::{
+        dialect::{set::into_set_hierarchy, Dialect},
+        misc::ParseOption,
+        ItemsContainer,
+    },
 };
 
+mod include;
+mod matcher;
 pub(crate) mod misc;
 pub(crate) mod options;
-pub use misc::ItemComplianceResult;
+pub mod remediation;
+pub mod report;
+pub(crate) mod typed;
+pub mod variables;
+pub use misc::{ComplianceError, ComplianceOk, ItemComplianceResult};
+pub use options::Severity;
+pub use report::{ComplianceReport, ComplianceReportNode, ComplianceSummary};
+pub use variables::{VariableError, Variables};
+
+pub fn check_compliance(policy: FlatConfigCompliance, config: FlatConfig) -> ComplianceReport {
+    let results = process_parent_compliance_check(&policy, &config);
+    ComplianceReport::new(policy.source, config.source, results)
+}
 
-//TODO: compliance result
-//TODO: ref result display/format
-pub fn check_compliance(
+/// Resolve the `{{ var }}` placeholders of the policy before checking it.
+pub fn check_compliance_with_vars(
     policy: FlatConfigCompliance,
     config: FlatConfig,
-) -> Vec<ItemComplianceResult> {
-    process_parent_compliance_check(&policy, &config)
+    vars: &Variables,
+) -> Result<ComplianceReport, VariableError> {
+    Ok(check_compliance(policy.with_variables(vars)?, config))
 }
 
 fn process_parent_compliance_check(
     policies: &(impl ItemsContainer + ComplianceOptionsContainer),
     same_level_item: &impl ItemsContainer,
-) -> Vec<ItemComplianceResult> {
-    let mut compliance_result: Vec<ItemComplianceResult> = vec![];
+) -> Vec<ComplianceReportNode> {
+    process_items_compliance_check(policies, same_level_item, &[Variables::new()]).nodes
+}
+
+/// Results of the policy items of a level.
+struct LevelCompliance {
+    nodes: Vec<ComplianceReportNode>,
+    /// Configuration items no policy item matched.
+    unmatched: Vec<FlatConfigItem>,
+    /// Bindings known after the level, when it captures global values.
+    exported: Option<Vec<Variables>>,
+}
+
+/// Check policy items against the configuration items of the same level.
+///
+/// Every binding context is a set of values captured by the regex named groups of
+/// the policy, an item using captured values is checked once per distinct values.
+fn process_items_compliance_check(
+    policies: &(impl ItemsContainer + ComplianceOptionsContainer),
+    same_level_item: &impl ItemsContainer,
+    contexts: &[Variables],
+) -> LevelCompliance {
+    let mut compliance_result: Vec<ComplianceReportNode> = vec![];
     let mut same_level_items = same_level_item.get_items().clone();
+    let mut contexts = contexts.to_vec();
+    let mut exports = false;
     for item in policies.get_items() {
-        let item_options = item.get_options();
-        let current_same_level_items = same_level_items.clone();
+        let options = item.get_options();
+        if let Some(ref when) = options.when {
+            let when = match KeyMatcher::new(when, MatchKind::Regex, &options) {
+                Ok(when) => when,
+                Err(err) => {
+                    compliance_result.push(ComplianceReportNode::new(
+                        ItemComplianceResult::new_invalid_pattern(
+                            item.clone(),
+                            when.clone(),
+                            err.to_string(),
+                        ),
+                        vec![],
+                    ));
+                    continue;
+                }
+            };
+            if !same_level_item
+                .get_items()
+                .iter()
+                .any(|i| when.is_match(i.get_item_key()))
+            {
+                compliance_result.push(ComplianceReportNode::new(
+                    ItemComplianceResult::new_not_applicable(item.clone()),
+                    vec![],
+                ));
+                continue;
+            }
+        }
 
-        let regex = if item_options.regex {
-            let regex = Regex::new(&format!("^{}$", item.get_item_key())).unwrap();
-            Some(regex)
+        // Values captured by the item itself are bound while checking it
+        let mut names = BTreeSet::new();
+        let mut own_names = BTreeSet::new();
+        variables::placeholder_names(item, &mut names);
+        variables::capture_names(std::slice::from_ref(item), &mut own_names);
+        names.retain(|name| !own_names.contains(name));
+        // Without any context left, items using captured values have nothing to check
+        let projections: BTreeSet<Variables> = if names.is_empty() {
+            BTreeSet::from([Variables::new()])
         } else {
-            None
+            contexts
+                .iter()
+                .map(|c| {
+                    c.iter()
+                        .filter(|(name, _)| names.contains(*name))
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect()
+                })
+                .collect()
         };
 
-        let predicate = |f: &&FlatConfigItem| -> bool {
-            let mut eq = item.eq(f);
+        let mut captured = vec![];
+        for bindings in projections {
+            let item = variables::bind_item(item, &bindings);
+            let item_options = item.get_options();
+            let (current_same_level_items, paths) =
+                if matches!(item_options.scope, ScopeOption::Descendants) {
+                    let mut descendants = (vec![], BTreeMap::new());
+                    collect_descendants(&same_level_items, &mut vec![], &mut descendants);
+                    descendants
+                } else {
+                    (same_level_items.clone(), BTreeMap::new())
+                };
+
+            // Replaced values can make a valid pattern invalid
+            let matchers =
+                KeyMatcher::new(item.get_item_key(), item_options.match_kind, &item_options)
+                    .map_err(|err| (String::from(item.get_item_key()), err))
+                    .and_then(|matcher| {
+                        let having = item_options
+                            .having
+                            .as_ref()
+                            .map(|having| {
+                                KeyMatcher::new(having, MatchKind::Regex, &item_options)
+                                    .map_err(|err| (having.clone(), err))
+                            })
+                            .transpose()?;
+                        Ok((matcher, having))
+                    });
+            let (matcher, having) = match matchers {
+                Ok(matchers) => matchers,
+                Err((pattern, err)) => {
+                    compliance_result.push(ComplianceReportNode::new(
+                        ItemComplianceResult::new_invalid_pattern(item, pattern, err.to_string())
+                            .with_bindings(bindings),
+                        vec![],
+                    ));
+                    continue;
+                }
+            };
 
-            if let Some(regex) = regex.clone() {
-                eq = regex.is_match(f.get_item_key());
-            }
+            let predicate = |f: &&FlatConfigItem| -> bool {
+                let mut eq = matcher.is_match(f.get_item_key());
 
-            if matches!(item_options.state, StateOption::Present) {
-                // is_variant_eq check eq enum variant type
-                eq = eq && item.is_variant_eq(f);
-            }
+                // Only select blocks with a matching child
+                if let Some(ref having) = having {
+                    eq = eq
+                        && matches!(f, FlatConfigItem::Parent(block) if block
+                            .items
+                            .iter()
+                            .any(|i| having.is_match(i.get_item_key())));
+                }
 
-            eq
-        };
+                if matches!(item_options.state, StateOption::Present) {
+                    // is_variant_eq check eq enum variant type
+                    eq = eq && item.is_variant_eq(f);
+                }
 
-        let matching_items: Vec<&FlatConfigItem> =
-            if matches!(item_options.r#match, MatchOption::First) {
-                current_same_level_items
-                    .iter()
-                    .find(predicate)
-                    .into_iter()
-                    .collect()
-            } else {
-                current_same_level_items.iter().filter(predicate).collect()
+                eq
             };
 
-        let mut cr = process_item_matches_compliance(item, matching_items.clone());
-        compliance_result.append(&mut cr);
+            let matching_items: Vec<&FlatConfigItem> =
+                if matches!(item_options.r#match, MatchOption::First) {
+                    current_same_level_items
+                        .iter()
+                        .find(predicate)
+                        .into_iter()
+                        .collect()
+                } else {
+                    current_same_level_items.iter().filter(predicate).collect()
+                };
+
+            let (mut cr, sets) = process_item_matches_compliance(
+                &item,
+                &matcher,
+                matching_items.clone(),
+                &current_same_level_items,
+                &bindings,
+            );
+            set_found_paths(&mut cr, &paths);
+            compliance_result.append(&mut cr);
+            captured.push((bindings, sets));
+
+            if !matching_items.is_empty() {
+                // items can match only once
+                let to_remove = BTreeSet::from_iter(matching_items);
+                same_level_items.retain(|f| !to_remove.contains(&f));
+            }
+        }
 
-        if !matching_items.is_empty() {
-            // items can match only once
-            let to_remove = BTreeSet::from_iter(matching_items);
-            same_level_items.retain(|f| !to_remove.contains(&f));
+        if has_global_binding(item) {
+            exports = true;
+            contexts = contexts
+                .iter()
+                .flat_map(|context| {
+                    captured
+                        .iter()
+                        .filter(|(bindings, _)| {
+                            bindings.iter().all(|(k, v)| context.get(k) == Some(v))
+                        })
+                        .flat_map(|(_, sets)| sets.iter())
+                        .map(|set| {
+                            let mut context = context.clone();
+                            context.extend(set.clone());
+                            context
+                        })
+                })
+                .collect();
+            contexts.sort();
+            contexts.dedup();
         }
     }
 
-    compliance_result
+    LevelCompliance {
+        nodes: compliance_result,
+        unmatched: same_level_items,
+        exported: exports.then_some(contexts),
+    }
+}
+
+/// Blocks above configuration items, by item index and key.
+type ItemPaths = BTreeMap<(usize, String), Vec<String>>;
+
+/// Flatten configuration items and their children at any depth, with their paths.
+fn collect_descendants(
+    items: &[FlatConfigItem],
+    path: &mut Vec<String>,
+    descendants: &mut (Vec<FlatConfigItem>, ItemPaths),
+) {
+    for item in items {
+        descendants.0.push(item.clone());
+        if !path.is_empty() {
+            let key = (item.get_index(), String::from(item.get_item_key()));
+            descendants.1.insert(key, path.clone());
+        }
+
+        if let FlatConfigItem::Parent(parent) = item {
+            path.push(parent.key.clone());
+            collect_descendants(&parent.items, path, descendants);
+            path.pop();
+        }
+    }
 }
 
+/// Report the blocks above the configuration items found in descendants.
+fn set_found_paths(nodes: &mut [ComplianceReportNode], paths: &ItemPaths) {
+    if paths.is_empty() {
+        return;
+    }
+
+    for node in nodes {
+        let item = match node.result.result {
+            Ok(ComplianceOk::IsPresent(ref item))
+            | Err(ComplianceError::ShouldBeAbsentIsPresent(ref item))
+            | Err(ComplianceError::ConstraintViolation { ref item, .. }) => item,
+            _ => continue,
+        };
+        let key = (item.get_index(), String::from(item.get_item_key()));
+        if let Some(path) = paths.get(&key) {
+            node.result.path = path.clone();
+        }
+    }
+}
+
+/// Whether an item or one of its children captures values for the following items.
+fn has_global_binding(item: &FlatConfigItem) -> bool {
+    let options = item.get_options();
+    if matches!(options.state, StateOption::Absent) {
+        return false;
+    }
+
+    matches!(options.bind, BindOption::Global)
+        || matches!(item, FlatConfigItem::Parent(parent) if parent.items.iter().any(has_global_binding))
+}
+
+/// Also return the binding contexts captured for the following items.
 fn process_item_matches_compliance(
     item: &FlatConfigItem,
+    matcher: &KeyMatcher,
     matches: Vec<&FlatConfigItem>,
-) -> Vec<ItemComplianceResult> {
-    let mut compliance_result: Vec<ItemComplianceResult> = vec![];
-
-    let state = item.get_options().state;
+    candidates: &[FlatConfigItem],
+    bindings: &Variables,
+) -> (Vec<ComplianceReportNode>, Vec<Variables>) {
+    let mut compliance_result: Vec<ComplianceReportNode> = vec![];
+    let mut captured = vec![];
+
+    let options = item.get_options();
+    let state = options.state;
     match state {
         StateOption::Present | StateOption::Optional => {
-            if matches.is_empty() {
-                if matches!(state, StateOption::Optional) {
-                    compliance_result.push(ItemComplianceResult::new_present_nok_ok(item.clone()));
+            if let Some(count) = options.count.filter(|c| !c.contains(matches.len())) {
+                compliance_result.push(ComplianceReportNode::new(
+                    ItemComplianceResult::new_count_mismatch(
+                        item.clone(),
+                        count,
+                        matches.into_iter().cloned().collect(),
+                    )
+                    .with_bindings(bindings.clone()),
+                    vec![],
+                ));
+            } else if matches.is_empty() {
+                // A line with a wrong value is reported rather than missing
+                let violation =
+                    candidates
+                        .iter()
+                        .find_map(|c| match matcher.check(c.get_item_key()) {
+                            KeyCheck::Violation { value, constraint } => {
+                                Some(ItemComplianceResult::new_constraint_violation(
+                                    item.clone(),
+                                    c.clone(),
+                                    value,
+                                    constraint,
+                                ))
+                            }
+                            _ => None,
+                        });
+                // Count options allowing no match make the item optional
+                let result = if let Some(violation) = violation {
+                    violation
+                } else if matches!(state, StateOption::Optional)
+                    || options.count.is_some()
+                    || matches!(options.for_each, Some(ForEachOption::OkIfNone))
+                {
+                    ItemComplianceResult::new_present_nok_ok(item.clone())
                 } else {
-                    compliance_result.push(ItemComplianceResult::new_present_nok(item.clone()));
-                }
+                    ItemComplianceResult::new_present_nok(item.clone())
+                };
+                compliance_result.push(ComplianceReportNode::new(
+                    result.with_bindings(bindings.clone()),
+                    vec![],
+                ));
             } else {
                 for matching_item in matches {
-                    compliance_result.push(ItemComplianceResult::new_present_ok(
-                        item.clone(),
-                        matching_item.clone(),
-                    ));
+                    let mut item_bindings = bindings.clone();
+                    item_bindings.extend(matcher.captured_values(matching_item.get_item_key()));
 
+                    let mut children = vec![];
+                    let mut exported = None;
                     if let FlatConfigItem::Parent(parent) = item {
                         if let FlatConfigItem::Parent(matching_parent) = matching_item {
-                            let mut cr = process_parent_compliance_check(parent, matching_parent);
-                            compliance_result.append(&mut cr);
+                            let level = process_items_compliance_check(
+                                parent,
+                                matching_parent,
+                                &[item_bindings.clone()],
+                            );
+                            children = level.nodes;
+                            exported = level.exported;
+
+                            if parent.options.exact {
+                                for unexpected in level.unmatched {
+                                    children.push(ComplianceReportNode::new(
+                                        ItemComplianceResult::new_unexpected(
+                                            item.clone(),
+                                            unexpected,
+                                        ),
+                                        vec![],
+                                    ));
+                                }
+                            }
+
+                            if let Some(order) = parent.options.order {
+                                process_order_compliance(order, &mut children, matching_parent);
+                            }
                         }
                     }
+
+                    if let Some(mut exported) = exported {
+                        captured.append(&mut exported);
+                    } else if matches!(options.bind, BindOption::Global) {
+                        captured.push(item_bindings.clone());
+                    }
+
+                    compliance_result.push(ComplianceReportNode::new(
+                        ItemComplianceResult::new_present_ok(item.clone(), matching_item.clone())
+                            .with_bindings(item_bindings),
+                        children,
+                    ));
                 }
             }
         }
         StateOption::Absent => {
             if matches.is_empty() {
-                compliance_result.push(ItemComplianceResult::new_absent_ok(item.clone()));
+                compliance_result.push(ComplianceReportNode::new(
+                    ItemComplianceResult::new_absent_ok(item.clone())
+                        .with_bindings(bindings.clone()),
+                    vec![],
+                ));
             } else {
                 for matching_item in matches {
-                    let matching_item = matching_item.clone();
-                    compliance_result.push(ItemComplianceResult::new_absent_nok(
-                        item.clone(),
-                        matching_item.clone(),
+                    compliance_result.push(ComplianceReportNode::new(
+                        ItemComplianceResult::new_absent_nok(item.clone(), matching_item.clone())
+                            .with_bindings(bindings.clone()),
+                        vec![],
                     ))
                 }
             }
         }
     }
-    compliance_result
+    (compliance_result, captured)
+}
+
+/// Replace the results of items found out of the order required by their parent policy.
+fn process_order_compliance(
+    order: OrderOption,
+    children: &mut [ComplianceReportNode],
+    config_block: &FlatConfigParent,
+) {
+    let mut rank = 0;
+    let mut last = 0;
+    for child in children.iter_mut() {
+        let Ok(ComplianceOk::IsPresent(ref item)) = child.result.result else {
+            continue;
+        };
+        let Some(found) = config_block
+            .items
+            .iter()
+            .position(|i| i.get_index() == item.get_index() && i == item)
+            .map(|p| p + 1)
+        else {
+            continue;
+        };
+        rank += 1;
+
+        let expected = match order {
+            OrderOption::Strict => rank,
+            OrderOption::Relative => last + 1,
+        };
+        if found == expected || matches!(order, OrderOption::Relative) && found > last {
+            last = found;
+            continue;
+        }
+
+        let result = ItemComplianceResult::new_out_of_order(
+            child.result.policy.clone(),
+            item.clone(),
+            expected,
+            found,
+        );
+        *child = ComplianceReportNode::new(result, std::mem::take(&mut child.children));
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -131,26 +475,83 @@ pub struct FlatConfigCompliance {
     // raw_content
     // compliance_option
     pub items: Vec<FlatConfigItem>,
+    pub source: Option<String>,
 }
 
 impl FlatConfigCompliance {
     pub fn new() -> Self {
         Self::default()
     }
-}
 
-impl FromStr for FlatConfigCompliance {
-    type Err = FlatConfigError;
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = Some(String::from(source));
+        self
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    pub fn from_str_with_dialect(s: &str, dialect: Dialect) -> Result<Self, FlatConfigError> {
+        Self::from_str_with_options(
+            s,
+            ParseOption {
+                dialect,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn from_str_with_options(s: &str, options: ParseOption) -> Result<Self, FlatConfigError> {
         if s.trim().is_empty() {
-            return Err(FlatConfigError::IO(io::Error::new(
-                io::ErrorKind::Other,
+            return Err(FlatConfigError::IO(io::Error::other(
+                "Input policy is empty.",
+            )));
+        }
+
+        Ok(Self {
+            items: PolicyLoader::new(options).load_str(s, None)?,
+            ..Default::default()
+        })
+    }
+
+    /// Read a policy file, its `#[include=path]` being relative to its directory.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FlatConfigError> {
+        Self::from_file_with_options(path, ParseOption::default())
+    }
+
+    pub fn from_file_with_options(
+        path: impl AsRef<Path>,
+        options: ParseOption,
+    ) -> Result<Self, FlatConfigError> {
+        let path = path.as_ref();
+        let items = PolicyLoader::new(options).load_file(path)?;
+        if items.is_empty() {
+            return Err(FlatConfigError::IO(io::Error::other(
                 "Input policy is empty.",
             )));
         }
 
-        Ok(parse_configuration(s, None)?)
+        Ok(Self {
+            items,
+            source: Some(path.display().to_string()),
+        })
+    }
+
+    /// Replace the `{{ var }}` placeholders, an unknown variable is an error.
+    pub fn with_variables(mut self, vars: &Variables) -> Result<Self, VariableError> {
+        variables::resolve_variables(&mut self.items, vars)?;
+        Ok(self)
+    }
+
+    /// Split every item into one level per word, to check a `set` dialect configuration.
+    pub fn into_set_hierarchy(mut self) -> Self {
+        self.items = into_set_hierarchy(self.items);
+        self
+    }
+}
+
+impl FromStr for FlatConfigCompliance {
+    type Err = FlatConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_dialect(s, Dialect::default())
     }
 }
 
@@ -189,7 +590,7 @@ impl ComplianceOptionsContainer for FlatConfigCompliance {
         unreachable!()
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, test))]
     fn get_raw_options(&self) -> &Vec<String> {
         unreachable!()
     }
@@ -10,6 +10,17 @@ pub enum ParseError {
     DuplicatedOption(String),
     InvalidOptionArgument(String, String),
     InvalidRegex(RegexError, String),
+    ConflictingOptions(String, String),
+    /// Typed placeholder and the reason it is invalid.
+    InvalidPlaceholder(String, String),
+    /// Reason the option line can't be read and its column.
+    InvalidSyntax(String, usize),
+    /// Directive sharing its line or its item with options.
+    MisplacedDirective(String),
+    UnknownSnippet(String),
+    DuplicatedSnippet(String),
+    /// Include of a policy which was not read from a file.
+    UnresolvedInclude(String),
 }
 
 impl error::Error for ParseError {}
@@ -38,6 +49,34 @@ impl fmt::Display for ParseError {
                     "Unable to parse option argument \"{arg}\" from \"{option}\""
                 )
             }
+            Self::ConflictingOptions(ref option, ref other) => {
+                write!(f, "Option \"{option}\" can't be used with \"{other}\".")
+            }
+            Self::InvalidPlaceholder(ref placeholder, ref reason) => {
+                write!(f, "Invalid placeholder \"{placeholder}\": {reason}")
+            }
+            // The column is given by the position of the wrapping error
+            Self::InvalidSyntax(ref reason, _) => {
+                write!(f, "Invalid option syntax: {reason}")
+            }
+            Self::MisplacedDirective(ref directive) => {
+                write!(
+                    f,
+                    "Directive \"{directive}\" must be alone on its line and can't follow options."
+                )
+            }
+            Self::UnknownSnippet(ref name) => {
+                write!(f, "Snippet \"{name}\" is not defined before its use.")
+            }
+            Self::DuplicatedSnippet(ref name) => {
+                write!(f, "Snippet \"{name}\" is defined more than once.")
+            }
+            Self::UnresolvedInclude(ref path) => {
+                write!(
+                    f,
+                    "Unable to include \"{path}\", only policies read from a file can include others."
+                )
+            }
             Self::InvalidRegex(ref err, ref key) => {
                 write!(f, "Regex error at line {key}. {err}")
             }
@@ -1,17 +1,34 @@
 pub mod error;
 pub(crate) mod parse;
+pub(crate) mod syntax;
 
 use std::fmt;
 
 use error::ParseError;
-use parse::parse_raw_options;
-use strum::EnumString;
+use strum::{EnumString, VariantNames};
 
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct ComplianceOptionsBuilder {
     pub(crate) regex: Option<bool>,
     pub(crate) state: Option<StateOption>,
     pub(crate) r#match: Option<MatchOption>,
+    pub(crate) exact: Option<bool>,
+    pub(crate) order: Option<OrderOption>,
+    pub(crate) count: Option<usize>,
+    pub(crate) min: Option<usize>,
+    pub(crate) max: Option<usize>,
+    pub(crate) bind: Option<BindOption>,
+    pub(crate) when: Option<String>,
+    pub(crate) for_each: Option<ForEachOption>,
+    pub(crate) having: Option<String>,
+    pub(crate) ignore_case: Option<bool>,
+    pub(crate) normalize_whitespace: Option<bool>,
+    pub(crate) match_kind: Option<MatchKind>,
+    pub(crate) scope: Option<ScopeOption>,
+    pub(crate) id: Option<String>,
+    pub(crate) severity: Option<Severity>,
+    pub(crate) description: Option<String>,
+    pub(crate) tags: Option<Vec<String>>,
 }
 
 impl ComplianceOptionsBuilder {
@@ -20,6 +37,13 @@ impl ComplianceOptionsBuilder {
             return Err(ParseError::DuplicatedOption("regex".to_string()));
         }
 
+        if self.match_kind.is_some() {
+            return Err(ParseError::ConflictingOptions(
+                "regex".to_string(),
+                "match-kind".to_string(),
+            ));
+        }
+
         self.regex = Some(regex);
         Ok(())
     }
@@ -28,6 +52,14 @@ impl ComplianceOptionsBuilder {
         if self.state.is_some() {
             return Err(ParseError::DuplicatedOption("state".to_string()));
         }
+        if matches!(state, StateOption::Absent) {
+            if let Some(other) = self.count_option_name() {
+                return Err(ParseError::ConflictingOptions(
+                    "state=absent".to_string(),
+                    other.to_string(),
+                ));
+            }
+        }
 
         self.state = Some(state);
         Ok(())
@@ -37,34 +69,327 @@ impl ComplianceOptionsBuilder {
         if self.r#match.is_some() {
             return Err(ParseError::DuplicatedOption("match".to_string()));
         }
+        if matches!(r#match, MatchOption::First) {
+            if let Some(other) = self.count_option_name() {
+                return Err(ParseError::ConflictingOptions(
+                    "match=first".to_string(),
+                    other.to_string(),
+                ));
+            }
+        }
 
         self.r#match = Some(r#match);
         Ok(())
     }
 
+    pub fn exact(&mut self, exact: bool) -> Result<(), ParseError> {
+        if self.exact.is_some() {
+            return Err(ParseError::DuplicatedOption("exact".to_string()));
+        }
+
+        self.exact = Some(exact);
+        Ok(())
+    }
+
+    pub fn order(&mut self, order: OrderOption) -> Result<(), ParseError> {
+        if self.order.is_some() {
+            return Err(ParseError::DuplicatedOption("order".to_string()));
+        }
+
+        self.order = Some(order);
+        Ok(())
+    }
+
+    pub fn count(&mut self, count: usize) -> Result<(), ParseError> {
+        if self.count.is_some() {
+            return Err(ParseError::DuplicatedOption("count".to_string()));
+        }
+        self.check_count_option("count")?;
+        if self.min.is_some() || self.max.is_some() {
+            let other = if self.min.is_some() { "min" } else { "max" };
+            return Err(ParseError::ConflictingOptions(
+                "count".to_string(),
+                other.to_string(),
+            ));
+        }
+
+        self.count = Some(count);
+        Ok(())
+    }
+
+    pub fn min(&mut self, min: usize) -> Result<(), ParseError> {
+        if self.min.is_some() {
+            return Err(ParseError::DuplicatedOption("min".to_string()));
+        }
+        self.check_count_option("min")?;
+        if self.count.is_some() {
+            return Err(ParseError::ConflictingOptions(
+                "min".to_string(),
+                "count".to_string(),
+            ));
+        }
+
+        if let Some(max) = self.max.filter(|max| min > *max) {
+            return Err(ParseError::ConflictingOptions(
+                format!("min={min}"),
+                format!("max={max}"),
+            ));
+        }
+
+        self.min = Some(min);
+        Ok(())
+    }
+
+    pub fn max(&mut self, max: usize) -> Result<(), ParseError> {
+        if self.max.is_some() {
+            return Err(ParseError::DuplicatedOption("max".to_string()));
+        }
+        self.check_count_option("max")?;
+        if self.count.is_some() {
+            return Err(ParseError::ConflictingOptions(
+                "max".to_string(),
+                "count".to_string(),
+            ));
+        }
+
+        if let Some(min) = self.min.filter(|min| *min > max) {
+            return Err(ParseError::ConflictingOptions(
+                format!("max={max}"),
+                format!("min={min}"),
+            ));
+        }
+
+        self.max = Some(max);
+        Ok(())
+    }
+
+    /// Name of the first count option set, if any.
+    fn count_option_name(&self) -> Option<&'static str> {
+        if self.count.is_some() {
+            Some("count")
+        } else if self.min.is_some() {
+            Some("min")
+        } else if self.max.is_some() {
+            Some("max")
+        } else {
+            None
+        }
+    }
+
+    /// Counting needs every match, absent items have none and `match=first` stops at one.
+    fn check_count_option(&self, option: &str) -> Result<(), ParseError> {
+        let other = match (self.state, self.r#match) {
+            (Some(StateOption::Absent), _) => "state=absent",
+            (_, Some(MatchOption::First)) => "match=first",
+            _ => return Ok(()),
+        };
+        Err(ParseError::ConflictingOptions(
+            option.to_string(),
+            other.to_string(),
+        ))
+    }
+
+    pub fn bind(&mut self, bind: BindOption) -> Result<(), ParseError> {
+        if self.bind.is_some() {
+            return Err(ParseError::DuplicatedOption("bind".to_string()));
+        }
+
+        self.bind = Some(bind);
+        Ok(())
+    }
+
+    pub fn when(&mut self, when: &str) -> Result<(), ParseError> {
+        if self.when.is_some() {
+            return Err(ParseError::DuplicatedOption("when".to_string()));
+        }
+
+        self.when = Some(String::from(when));
+        Ok(())
+    }
+
+    pub fn for_each(&mut self, for_each: ForEachOption) -> Result<(), ParseError> {
+        if self.for_each.is_some() {
+            return Err(ParseError::DuplicatedOption("for-each".to_string()));
+        }
+
+        self.for_each = Some(for_each);
+        Ok(())
+    }
+
+    pub fn having(&mut self, having: &str) -> Result<(), ParseError> {
+        if self.having.is_some() {
+            return Err(ParseError::DuplicatedOption("having".to_string()));
+        }
+
+        self.having = Some(String::from(having));
+        Ok(())
+    }
+
+    pub fn ignore_case(&mut self, ignore_case: bool) -> Result<(), ParseError> {
+        if self.ignore_case.is_some() {
+            return Err(ParseError::DuplicatedOption("ignore-case".to_string()));
+        }
+
+        self.ignore_case = Some(ignore_case);
+        Ok(())
+    }
+
+    pub fn normalize_whitespace(&mut self, normalize_whitespace: bool) -> Result<(), ParseError> {
+        if self.normalize_whitespace.is_some() {
+            return Err(ParseError::DuplicatedOption(
+                "normalize-whitespace".to_string(),
+            ));
+        }
+
+        self.normalize_whitespace = Some(normalize_whitespace);
+        Ok(())
+    }
+
+    pub fn match_kind(&mut self, match_kind: MatchKind) -> Result<(), ParseError> {
+        if self.match_kind.is_some() {
+            return Err(ParseError::DuplicatedOption("match-kind".to_string()));
+        }
+
+        if self.regex.is_some() {
+            return Err(ParseError::ConflictingOptions(
+                "match-kind".to_string(),
+                "regex".to_string(),
+            ));
+        }
+
+        self.match_kind = Some(match_kind);
+        Ok(())
+    }
+
+    pub fn scope(&mut self, scope: ScopeOption) -> Result<(), ParseError> {
+        if self.scope.is_some() {
+            return Err(ParseError::DuplicatedOption("scope".to_string()));
+        }
+
+        self.scope = Some(scope);
+        Ok(())
+    }
+
+    pub fn id(&mut self, id: &str) -> Result<(), ParseError> {
+        if self.id.is_some() {
+            return Err(ParseError::DuplicatedOption("id".to_string()));
+        }
+
+        self.id = Some(String::from(id));
+        Ok(())
+    }
+
+    pub fn severity(&mut self, severity: Severity) -> Result<(), ParseError> {
+        if self.severity.is_some() {
+            return Err(ParseError::DuplicatedOption("severity".to_string()));
+        }
+
+        self.severity = Some(severity);
+        Ok(())
+    }
+
+    pub fn description(&mut self, description: &str) -> Result<(), ParseError> {
+        if self.description.is_some() {
+            return Err(ParseError::DuplicatedOption("description".to_string()));
+        }
+
+        self.description = Some(String::from(description));
+        Ok(())
+    }
+
+    pub fn tags(&mut self, tags: Vec<String>) -> Result<(), ParseError> {
+        if self.tags.is_some() {
+            return Err(ParseError::DuplicatedOption("tags".to_string()));
+        }
+
+        self.tags = Some(tags);
+        Ok(())
+    }
+
     pub fn build(self) -> ComplianceOptions {
-        let regex = self.regex.unwrap_or(false);
+        // Blocks are selected by pattern
+        let match_kind =
+            self.match_kind
+                .unwrap_or(if self.regex.unwrap_or(false) || self.for_each.is_some() {
+                    MatchKind::Regex
+                } else {
+                    MatchKind::default()
+                });
+        let regex = matches!(match_kind, MatchKind::Regex);
         let state = self.state.unwrap_or_default();
+        let count = match (self.count, self.min, self.max) {
+            (Some(count), _, _) => Some(CountOption::new(Some(count), Some(count))),
+            (None, None, None) => None,
+            (None, min, max) => Some(CountOption::new(min, max)),
+        };
         ComplianceOptions {
             regex,
             state,
-            r#match: self
-                .r#match
-                .unwrap_or(if regex | matches!(state, StateOption::Absent) {
+            r#match: self.r#match.unwrap_or(
+                // Every match is needed to count them
+                if match_kind.is_pattern()
+                    || matches!(state, StateOption::Absent)
+                    || count.is_some()
+                {
                     MatchOption::All
                 } else {
                     MatchOption::default()
-                }),
+                },
+            ),
+            exact: self.exact.unwrap_or(false),
+            order: self.order,
+            count,
+            bind: self.bind.unwrap_or_default(),
+            when: self.when,
+            for_each: self.for_each,
+            having: self.having,
+            ignore_case: self.ignore_case.unwrap_or(false),
+            normalize_whitespace: self.normalize_whitespace.unwrap_or(false),
+            match_kind,
+            scope: self.scope.unwrap_or_default(),
+            id: self.id,
+            severity: self.severity,
+            description: self.description,
+            tags: self.tags.unwrap_or_default(),
         }
     }
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ComplianceOptions {
     pub(crate) regex: bool,
     pub(crate) state: StateOption,
     pub(crate) r#match: MatchOption,
+    /// Configuration block must not contain items the policy does not list.
+    pub(crate) exact: bool,
+    /// Matched configuration children must follow the policy order.
+    pub(crate) order: Option<OrderOption>,
+    /// Number of configuration items the policy item must match.
+    pub(crate) count: Option<CountOption>,
+    /// Scope of the values captured by the named groups of a regex item.
+    pub(crate) bind: BindOption,
+    /// Regex one of the same level configuration items must match for the item to apply.
+    pub(crate) when: Option<String>,
+    /// Children are checked in every selected block.
+    pub(crate) for_each: Option<ForEachOption>,
+    /// Regex one of the children of a configuration block must match to be selected.
+    pub(crate) having: Option<String>,
+    /// Keys are compared regardless of the case.
+    pub(crate) ignore_case: bool,
+    /// Keys are compared with single spaces between words.
+    pub(crate) normalize_whitespace: bool,
+    /// How the key is compared, `regex` is set for the regex kind.
+    pub(crate) match_kind: MatchKind,
+    /// Configuration items compared with the policy item.
+    pub(crate) scope: ScopeOption,
+    /// Identifier of the rule, reported with the results.
+    pub(crate) id: Option<String>,
+    /// Impact of a failure, inherited by the children without one.
+    pub(crate) severity: Option<Severity>,
+    /// What the rule checks and why.
+    pub(crate) description: Option<String>,
+    pub(crate) tags: Vec<String>,
 }
 
 impl ComplianceOptions {
@@ -72,10 +397,11 @@ impl ComplianceOptions {
         ComplianceOptionsBuilder::default()
     }
 
+    #[cfg(test)]
     pub(crate) fn new_from_vec(options: &[String]) -> Result<Self, ParseError> {
         let mut compliance_option_builder = Self::builder();
 
-        parse_raw_options(&mut compliance_option_builder, options)?;
+        parse::parse_raw_options(&mut compliance_option_builder, options)?;
 
         Ok(compliance_option_builder.build())
     }
@@ -118,10 +444,157 @@ impl fmt::Display for StateOption {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Copy, Clone, EnumString, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "kebab-case")]
+pub enum OrderOption {
+    /// Matched items are the first items of the block, in the policy order
+    Strict,
+    /// Matched items are in the policy order, other items can be in between
+    Relative,
+}
+
+impl fmt::Display for OrderOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, EnumString, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BindOption {
+    /// Captured values are only known by the children of the matched block
+    #[default]
+    Block,
+    /// Captured values are also known by the policy items following the item
+    Global,
+}
+
+impl fmt::Display for BindOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, EnumString, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ForEachOption {
+    /// At least one block must be selected
+    #[default]
+    Required,
+    /// Selecting no block is ok
+    OkIfNone,
+}
+
+impl fmt::Display for ForEachOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, EnumString, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "kebab-case")]
+pub enum MatchKind {
+    /// Keys are equal
+    #[default]
+    Exact,
+    /// Key matches the whole regex
+    Regex,
+    /// Key starts with the policy key
+    Prefix,
+    /// Key ends with the policy key
+    Suffix,
+    /// Key contains the policy key
+    Contains,
+    /// Key matches the shell pattern, with `*`, `?` and `[...]`
+    Glob,
+}
+
+impl MatchKind {
+    /// The policy key is not a configuration line.
+    pub fn is_pattern(&self) -> bool {
+        !matches!(self, Self::Exact)
+    }
+}
+
+impl fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, EnumString, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ScopeOption {
+    /// Items of the same level
+    #[default]
+    Level,
+    /// Items of the same level and all their children, at any depth
+    Descendants,
+}
+
+impl fmt::Display for ScopeOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Impact of a failed policy item, from the least to the most severe.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Copy, Clone, EnumString, VariantNames, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Bounds of the number of matches, both included.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CountOption {
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+}
+
+impl CountOption {
+    pub fn new(min: Option<usize>, max: Option<usize>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn contains(&self, count: usize) -> bool {
+        self.min.is_none_or(|min| count >= min) && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+impl fmt::Display for CountOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => write!(f, "exactly {min}"),
+            (Some(min), Some(max)) => write!(f, "between {min} and {max}"),
+            (Some(min), None) => write!(f, "at least {min}"),
+            (None, Some(max)) => write!(f, "at most {max}"),
+            (None, None) => write!(f, "any number"),
+        }
+    }
+}
+
 pub trait ComplianceOptionsContainer {
     fn get_options(&self) -> ComplianceOptions;
     fn set_options(&mut self, options: ComplianceOptions);
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, test))]
     fn get_raw_options(&self) -> &Vec<String>;
     #[cfg(debug_assertions)]
     fn set_raw_options(&mut self, options: &[String]);
@@ -129,7 +602,14 @@ pub trait ComplianceOptionsContainer {
 
 #[cfg(test)]
 mod tests {
+    use super::BindOption;
     use super::ComplianceOptions;
+    use super::ForEachOption;
+    use super::MatchKind;
+    use super::MatchOption;
+    use super::ParseError;
+    use super::ScopeOption;
+    use super::Severity;
     use super::StateOption;
 
     #[test]
@@ -137,6 +617,167 @@ mod tests {
         let options = ComplianceOptions::default();
 
         assert!(!options.regex);
+        assert!(!options.exact);
         assert!(matches!(options.state, StateOption::Present));
     }
+
+    #[test]
+    fn test_fcc_options_exact() {
+        let options = ComplianceOptions::new_from_vec(&[String::from("#[exact]")]).unwrap();
+        assert!(options.exact);
+
+        let err =
+            ComplianceOptions::new_from_vec(&[String::from("#[exact]"), String::from("#[exact]")]);
+        assert!(matches!(err, Err(ParseError::DuplicatedOption(_))));
+    }
+
+    #[test]
+    fn test_fcc_options_count() {
+        let options = ComplianceOptions::new_from_vec(&[String::from("#[count=2]")]).unwrap();
+        let count = options.count.unwrap();
+        assert!(count.contains(2));
+        assert!(!count.contains(3));
+        assert_eq!(count.to_string(), "exactly 2");
+        assert!(matches!(options.r#match, MatchOption::All));
+
+        let options =
+            ComplianceOptions::new_from_vec(&[String::from("#[min=1]"), String::from("#[max=4]")])
+                .unwrap();
+        assert_eq!(options.count.unwrap().to_string(), "between 1 and 4");
+
+        let err = ComplianceOptions::new_from_vec(&[
+            String::from("#[min=1]"),
+            String::from("#[count=2]"),
+        ]);
+        assert!(matches!(err, Err(ParseError::ConflictingOptions(_, _))));
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[max=many]")]);
+        assert!(matches!(err, Err(ParseError::InvalidOptionArgument(_, _))));
+    }
+
+    #[test]
+    fn test_fcc_options_bind() {
+        let options = ComplianceOptions::default();
+        assert!(matches!(options.bind, BindOption::Block));
+
+        let options = ComplianceOptions::new_from_vec(&[String::from("#[bind=global]")]).unwrap();
+        assert!(matches!(options.bind, BindOption::Global));
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[bind=local]")]);
+        assert!(matches!(err, Err(ParseError::InvalidOptionArgument(_, _))));
+    }
+
+    #[test]
+    fn test_fcc_options_when() {
+        let options =
+            ComplianceOptions::new_from_vec(&[String::from("#[when=router bgp \\d+]")]).unwrap();
+        assert_eq!(options.when.as_deref(), Some(r"router bgp \d+"));
+
+        let options =
+            ComplianceOptions::new_from_vec(&[String::from("#[when=switchport [a-z]+ access]")])
+                .unwrap();
+        assert_eq!(options.when.as_deref(), Some("switchport [a-z]+ access"));
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[when=vlan (]")]);
+        assert!(matches!(err, Err(ParseError::InvalidRegex(_, _))));
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[when]")]);
+        assert!(matches!(err, Err(ParseError::MalformedOption(_))));
+    }
+
+    #[test]
+    fn test_fcc_options_for_each() {
+        let options = ComplianceOptions::new_from_vec(&[
+            String::from("#[for-each]"),
+            String::from("#[having=switchport mode access]"),
+        ])
+        .unwrap();
+        assert!(matches!(options.for_each, Some(ForEachOption::Required)));
+        assert_eq!(options.having.as_deref(), Some("switchport mode access"));
+        assert!(options.regex);
+        assert!(matches!(options.r#match, MatchOption::All));
+
+        let options =
+            ComplianceOptions::new_from_vec(&[String::from("#[for-each=ok-if-none]")]).unwrap();
+        assert!(matches!(options.for_each, Some(ForEachOption::OkIfNone)));
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[for-each=some]")]);
+        assert!(matches!(err, Err(ParseError::InvalidOptionArgument(_, _))));
+    }
+
+    #[test]
+    fn test_fcc_options_match_modes() {
+        let options = ComplianceOptions::new_from_vec(&[
+            String::from("#[ignore-case]"),
+            String::from("#[normalize-whitespace]"),
+        ])
+        .unwrap();
+        assert!(options.ignore_case);
+        assert!(options.normalize_whitespace);
+
+        let err = ComplianceOptions::new_from_vec(&[
+            String::from("#[ignore-case]"),
+            String::from("#[ignore-case]"),
+        ]);
+        assert!(matches!(err, Err(ParseError::DuplicatedOption(_))));
+    }
+
+    #[test]
+    fn test_fcc_options_match_kind() {
+        let options =
+            ComplianceOptions::new_from_vec(&[String::from("#[match-kind=prefix]")]).unwrap();
+        assert!(matches!(options.match_kind, MatchKind::Prefix));
+        assert!(matches!(options.r#match, MatchOption::All));
+        assert!(!options.regex);
+
+        let options = ComplianceOptions::new_from_vec(&[String::from("#[regex]")]).unwrap();
+        assert!(matches!(options.match_kind, MatchKind::Regex));
+
+        let err = ComplianceOptions::new_from_vec(&[
+            String::from("#[regex]"),
+            String::from("#[match-kind=glob]"),
+        ]);
+        assert!(matches!(err, Err(ParseError::ConflictingOptions(_, _))));
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[match-kind=fuzzy]")]);
+        assert!(matches!(err, Err(ParseError::InvalidOptionArgument(_, _))));
+    }
+
+    #[test]
+    fn test_fcc_options_scope() {
+        let options = ComplianceOptions::default();
+        assert!(matches!(options.scope, ScopeOption::Level));
+
+        let options =
+            ComplianceOptions::new_from_vec(&[String::from("#[scope=descendants]")]).unwrap();
+        assert!(matches!(options.scope, ScopeOption::Descendants));
+    }
+
+    #[test]
+    fn test_fcc_options_rule() {
+        let options = ComplianceOptions::new_from_vec(&[
+            String::from("#[id=SEC-001]"),
+            String::from("#[severity=critical]"),
+            String::from("#[description=Telnet sends passwords in clear text]"),
+            String::from("#[tags=security, cis]"),
+        ])
+        .unwrap();
+        assert_eq!(options.id.as_deref(), Some("SEC-001"));
+        assert_eq!(options.severity, Some(Severity::Critical));
+        assert_eq!(
+            options.description.as_deref(),
+            Some("Telnet sends passwords in clear text")
+        );
+        assert_eq!(options.tags, ["security", "cis"]);
+        assert!(Severity::High > Severity::Medium);
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[severity=urgent]")]);
+        assert!(matches!(err, Err(ParseError::InvalidOptionArgument(_, _))));
+
+        let err = ComplianceOptions::new_from_vec(&[
+            String::from("#[tags=cis]"),
+            String::from("#[tags=pci]"),
+        ]);
+        assert!(matches!(err, Err(ParseError::DuplicatedOption(_))));
+    }
 }
@@ -3,36 +3,77 @@ use std::str::FromStr;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::{config::FlatConfigItem, parse::ItemsContainer};
+use crate::{
+    compliance::{
+        include::{directive, Directive},
+        matcher::key_pattern,
+        typed::typed_placeholders,
+        variables::escape_placeholders,
+    },
+    config::FlatConfigItem,
+    parse::{error::ParseError as FlatParseError, span::Position, ItemsContainer},
+};
 
 use super::{
-    error::ParseError, ComplianceOptions, ComplianceOptionsBuilder, ComplianceOptionsContainer,
-    MatchOption, StateOption,
+    error::ParseError,
+    syntax::{parse_option_line, RawOption},
+    ComplianceOptions, ComplianceOptionsBuilder, ComplianceOptionsContainer, ForEachOption,
+    Severity, StateOption,
 };
 
 lazy_static! {
-    // USE TO CAPTURE OPTION
-    pub static ref COMPLIANCE_OPTION_REGEX: Regex = Regex::new(r"^[^\S\r\n]*#\[(?<option>\w+)(=(?<arg>[\w-]+))?][^\S\r\n]*$").unwrap();
+    // USE TO RECOGNIZE OPTION LINES, read by parse_option_line
+    pub static ref COMPLIANCE_OPTION_REGEX: Regex = Regex::new(r"^[^\S\r\n]*#\[.*\][^\S\r\n]*$").unwrap();
 }
 
-pub(crate) fn process_fcc_options(parent: &mut dyn ItemsContainer) -> Result<(), ParseError> {
+pub(crate) fn process_fcc_options(parent: &mut dyn ItemsContainer) -> Result<(), FlatParseError> {
     let mut items: Vec<FlatConfigItem> = vec![];
-    let mut item_options: Vec<String> = vec![];
+    let mut item_options: Vec<FlatConfigItem> = vec![];
 
     for i in parent.get_items() {
         let item = i.to_owned().clone();
         let key = item.get_item_key();
+        let position = item.get_position();
 
         if COMPLIANCE_OPTION_REGEX.is_match(key) {
+            if let Some(directive) = directive(key) {
+                // Directives are expanded by the policy loader, only snippets have children
+                let err = match (directive, &item) {
+                    _ if !item_options.is_empty() => {
+                        Some(ParseError::MisplacedDirective(String::from(key)))
+                    }
+                    (Directive::Define(_), FlatConfigItem::Line(_)) => {
+                        Some(ParseError::MalformedOption(String::from(key)))
+                    }
+                    (Directive::Include(_) | Directive::Use(_), FlatConfigItem::Parent(_)) => {
+                        Some(ParseError::BadIndentation(String::from(key)))
+                    }
+                    _ => None,
+                };
+                if let Some(err) = err {
+                    return Err(FlatParseError::ComplianceOption(err, position));
+                }
+
+                let mut item = item;
+                if let FlatConfigItem::Parent(ref mut parent) = item {
+                    process_fcc_options(parent)?;
+                }
+                items.push(item);
+                continue;
+            }
+
             if matches!(item, FlatConfigItem::Parent(_)) {
                 // Prevent something like that
                 //
                 // #[debug]
                 //     line1
                 //
-                return Err(ParseError::BadIndentation(String::from(key)));
+                return Err(FlatParseError::ComplianceOption(
+                    ParseError::BadIndentation(String::from(key)),
+                    position,
+                ));
             }
-            item_options.push(String::from(key));
+            item_options.push(item);
             continue;
         }
         // item does not match COMPLIANCE_OPTION_REGEX
@@ -41,16 +82,49 @@ pub(crate) fn process_fcc_options(parent: &mut dyn ItemsContainer) -> Result<(),
         // Parse list of string options into ComplianceOptions
         if !item_options.is_empty() {
             #[cfg(debug_assertions)]
-            item_with_options.set_raw_options(&item_options);
-            let compliance_options = ComplianceOptions::new_from_vec(&item_options)?;
-            item_with_options.set_options(compliance_options);
+            item_with_options.set_raw_options(
+                &item_options
+                    .iter()
+                    .map(|o| String::from(o.get_item_key()))
+                    .collect::<Vec<String>>(),
+            );
+            let mut compliance_option_builder = ComplianceOptions::builder();
+            for option in &item_options {
+                let position = option.get_position();
+                // Columns of the option line start on the "#"
+                let at = |column: usize| Position::new(position.line, position.column + column - 1);
+                let raw_options =
+                    parse_option_line(option.get_item_key()).map_err(|err| match err {
+                        ParseError::InvalidSyntax(reason, column) => {
+                            FlatParseError::ComplianceOption(
+                                ParseError::InvalidSyntax(reason, at(column).column),
+                                at(column),
+                            )
+                        }
+                        err => FlatParseError::ComplianceOption(err, position),
+                    })?;
+                for raw_option in &raw_options {
+                    apply_option(&mut compliance_option_builder, raw_option).map_err(|err| {
+                        let column = raw_option.error_column(&err);
+                        FlatParseError::ComplianceOption(err, at(column))
+                    })?;
+                }
+            }
+            item_with_options.set_options(compliance_option_builder.build());
             item_options.clear();
         }
 
+        typed_placeholders(item_with_options.get_item_key())
+            .map_err(|err| FlatParseError::ComplianceOption(err, position))?;
+
         // Check regex synthax
-        if item_with_options.get_options().regex {
-            Regex::new(&format!("^{}$", item_with_options.get_item_key())).map_err(|err| {
-                ParseError::InvalidRegex(err, item_with_options.get_item_key().to_string())
+        let match_kind = item_with_options.get_options().match_kind;
+        if let Some(pattern) = key_pattern(item_with_options.get_item_key(), match_kind) {
+            Regex::new(&pattern).map_err(|err| {
+                FlatParseError::ComplianceOption(
+                    ParseError::InvalidRegex(err, item_with_options.get_item_key().to_string()),
+                    position,
+                )
             })?;
         }
 
@@ -60,6 +134,9 @@ pub(crate) fn process_fcc_options(parent: &mut dyn ItemsContainer) -> Result<(),
                 item_with_options = FlatConfigItem::Line(parent.clone().into())
             } else {
                 process_fcc_options(parent)?;
+                if let Some(severity) = parent.options.severity {
+                    inherit_severity(&mut parent.items, severity);
+                }
                 if parent.items.is_empty() {
                     item_with_options = FlatConfigItem::Line(parent.clone().into())
                 }
@@ -74,53 +151,109 @@ pub(crate) fn process_fcc_options(parent: &mut dyn ItemsContainer) -> Result<(),
     Ok(())
 }
 
+/// Rate the items without severity, and their children, as their parent block.
+fn inherit_severity(items: &mut [FlatConfigItem], severity: Severity) {
+    for item in items {
+        let mut options = item.get_options();
+        let severity = *options.severity.get_or_insert(severity);
+        item.set_options(options);
+
+        if let FlatConfigItem::Parent(parent) = item {
+            inherit_severity(&mut parent.items, severity);
+        }
+    }
+}
+
+/// Flag options take no argument.
+fn parse_flag(option: &RawOption) -> Result<bool, ParseError> {
+    match option.arg {
+        Some(_) => Err(ParseError::MalformedOption(option.name.clone())),
+        None => Ok(true),
+    }
+}
+
+/// Free text argument, surrounding whitespaces removed.
+fn parse_text_argument(option: &RawOption) -> Result<&str, ParseError> {
+    match option.arg.as_deref().map(str::trim) {
+        Some(arg) if !arg.is_empty() => Ok(arg),
+        _ => Err(ParseError::MalformedOption(option.name.clone())),
+    }
+}
+
+fn parse_count_argument(option: &RawOption) -> Result<usize, ParseError> {
+    let arg = parse_text_argument(option)?;
+    usize::from_str(arg)
+        .map_err(|_| ParseError::InvalidOptionArgument(String::from(arg), option.name.clone()))
+}
+
+/// Kebab-case name of one of the values of an option.
+fn parse_enum_argument<T: FromStr>(option: &RawOption) -> Result<T, ParseError> {
+    let arg = parse_text_argument(option)?;
+    T::from_str(arg)
+        .map_err(|_| ParseError::InvalidOptionArgument(String::from(arg), option.name.clone()))
+}
+
+/// Regex argument matched against configuration items, checked when parsed.
+fn parse_regex_argument(option: &RawOption) -> Result<&str, ParseError> {
+    let arg = parse_text_argument(option)?;
+    Regex::new(&format!("(?s)^{}$", escape_placeholders(arg)))
+        .map_err(|err| ParseError::InvalidRegex(err, String::from(arg)))?;
+    Ok(arg)
+}
+
+fn apply_option(
+    compliance_option: &mut ComplianceOptionsBuilder,
+    option: &RawOption,
+) -> Result<(), ParseError> {
+    match option.name.as_str() {
+        "regex" => compliance_option.regex(parse_flag(option)?),
+        "exact" => compliance_option.exact(parse_flag(option)?),
+        "ignore-case" => compliance_option.ignore_case(parse_flag(option)?),
+        "normalize-whitespace" => compliance_option.normalize_whitespace(parse_flag(option)?),
+        "state" => compliance_option.state(parse_enum_argument(option)?),
+        "count" => compliance_option.count(parse_count_argument(option)?),
+        "min" => compliance_option.min(parse_count_argument(option)?),
+        "max" => compliance_option.max(parse_count_argument(option)?),
+        "order" => compliance_option.order(parse_enum_argument(option)?),
+        "match-kind" => compliance_option.match_kind(parse_enum_argument(option)?),
+        "scope" => compliance_option.scope(parse_enum_argument(option)?),
+        "bind" => compliance_option.bind(parse_enum_argument(option)?),
+        "id" => compliance_option.id(parse_text_argument(option)?),
+        "description" => compliance_option.description(parse_text_argument(option)?),
+        "tags" => compliance_option.tags(
+            parse_text_argument(option)?
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(String::from)
+                .collect(),
+        ),
+        "severity" => compliance_option.severity(parse_enum_argument(option)?),
+        "when" => compliance_option.when(parse_regex_argument(option)?),
+        "having" => compliance_option.having(parse_regex_argument(option)?),
+        "for-each" => compliance_option.for_each(match option.arg {
+            Some(_) => parse_enum_argument(option)?,
+            None => ForEachOption::default(),
+        }),
+        "match" => compliance_option.r#match(parse_enum_argument(option)?),
+        "include" | "use" | "define" => match option.arg {
+            Some(_) => Err(ParseError::MisplacedDirective(option.name.clone())),
+            None => Err(ParseError::MalformedOption(option.name.clone())),
+        },
+        #[cfg(debug_assertions)]
+        "debug" => Ok(()),
+        _ => Err(ParseError::UnknowOption(option.name.clone())),
+    }
+}
+
+#[cfg(test)]
 pub(super) fn parse_raw_options(
     compliance_option: &mut ComplianceOptionsBuilder,
     raw_options: &[String],
 ) -> Result<(), ParseError> {
-    for option in raw_options {
-        if let Some(caps) = COMPLIANCE_OPTION_REGEX.captures(option) {
-            let o = &caps["option"];
-            match o {
-                "regex" => {
-                    compliance_option.regex(true)?;
-                }
-                "state" => {
-                    if let Some(arg) = caps.name("arg") {
-                        compliance_option.state(StateOption::from_str(arg.as_str()).map_err(
-                            |_| {
-                                ParseError::InvalidOptionArgument(
-                                    String::from(arg.as_str()),
-                                    String::from(option),
-                                )
-                            },
-                        )?)?;
-                    } else {
-                        return Err(ParseError::MalformedOption(String::from(option)));
-                    }
-                }
-                "match" => {
-                    if let Some(arg) = caps.name("arg") {
-                        compliance_option.r#match(MatchOption::from_str(arg.as_str()).map_err(
-                            |_| {
-                                ParseError::InvalidOptionArgument(
-                                    String::from(arg.as_str()),
-                                    String::from(option),
-                                )
-                            },
-                        )?)?;
-                    } else {
-                        return Err(ParseError::MalformedOption(String::from(option)));
-                    }
-                }
-                #[cfg(debug_assertions)]
-                "debug" => {
-                    continue;
-                }
-                _ => return Err(ParseError::UnknowOption(String::from(o))),
-            }
-        } else {
-            return Err(ParseError::MalformedOption(String::from(option)));
+    for line in raw_options {
+        for option in parse_option_line(line)? {
+            apply_option(compliance_option, &option)?;
         }
     }
     Ok(())
@@ -129,6 +262,7 @@ pub(super) fn parse_raw_options(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compliance::options::{MatchKind, MatchOption};
 
     #[cfg(not(debug_assertions))]
     use crate::{config::FlatConfigParent, parse::process_next_indent_level};
@@ -246,7 +380,10 @@ mod tests {
 
         let err = process_fcc_options(&mut config).unwrap_err();
 
-        assert!(matches!(err, ParseError::BadIndentation(_)));
+        assert!(matches!(
+            err,
+            FlatParseError::ComplianceOption(ParseError::BadIndentation(_), _)
+        ));
     }
 
     #[test]
@@ -283,10 +420,56 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(
             result.err().unwrap(),
-            ParseError::InvalidRegex(_, _)
+            FlatParseError::ComplianceOption(ParseError::InvalidRegex(_, _), _)
         ));
     }
 
+    #[test]
+    fn test_process_fcc_options_invalid_glob() {
+        let mut config = FlatConfigParent::default();
+        let mut lines = include_str!("../../../test/process_fcc_options/7.txt")
+            .lines()
+            .map(String::from)
+            .enumerate()
+            .peekable();
+
+        process_next_indent_level(&mut lines, &mut config).unwrap();
+        let result = process_fcc_options(&mut config);
+
+        assert!(matches!(
+            result.err().unwrap(),
+            FlatParseError::ComplianceOption(ParseError::InvalidRegex(_, _), _)
+        ));
+    }
+
+    #[test]
+    fn test_process_fcc_options_several_per_line() {
+        let mut config = FlatConfigParent::default();
+        let mut lines = include_str!("../../../test/process_fcc_options/8.txt")
+            .lines()
+            .map(String::from)
+            .enumerate()
+            .peekable();
+
+        process_next_indent_level(&mut lines, &mut config).unwrap();
+        process_fcc_options(&mut config).unwrap();
+
+        let FlatConfigItem::Parent(ref parent) = config.items[0] else {
+            panic!()
+        };
+        let options = parent.get_options();
+        assert!(options.regex);
+        assert!(matches!(options.state, StateOption::Optional));
+        assert_eq!(
+            options.description.as_deref(),
+            Some("Any \"vty\" line, ssh only")
+        );
+
+        let options = parent.items[0].get_options();
+        assert!(matches!(options.match_kind, MatchKind::Glob));
+        assert!(options.ignore_case);
+    }
+
     #[test]
     fn test_parse_fcc_options_1() {
         let options = vec![
@@ -398,4 +581,59 @@ mod tests {
         .unwrap_err();
         assert!(matches!(err, ParseError::DuplicatedOption(_)));
     }
+
+    #[test]
+    fn test_parse_fcc_options_count_absent() {
+        for option in ["count=2", "min=1", "max=3"] {
+            let err = ComplianceOptions::new_from_vec(&[format!("#[state=absent, {option}]")])
+                .unwrap_err();
+            assert!(
+                matches!(err, ParseError::ConflictingOptions(_, _)),
+                "{option}"
+            );
+
+            let err = ComplianceOptions::new_from_vec(&[format!("#[{option}, state=absent]")])
+                .unwrap_err();
+            assert!(
+                matches!(err, ParseError::ConflictingOptions(_, _)),
+                "{option}"
+            );
+        }
+
+        assert!(
+            ComplianceOptions::new_from_vec(&[String::from("#[state=optional, max=3]")]).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_fcc_options_count_match_first() {
+        for option in ["count=2", "min=1", "max=3"] {
+            let err = ComplianceOptions::new_from_vec(&[format!("#[match=first, {option}]")])
+                .unwrap_err();
+            assert!(
+                matches!(err, ParseError::ConflictingOptions(_, _)),
+                "{option}"
+            );
+
+            let err = ComplianceOptions::new_from_vec(&[format!("#[{option}, match=first]")])
+                .unwrap_err();
+            assert!(
+                matches!(err, ParseError::ConflictingOptions(_, _)),
+                "{option}"
+            );
+        }
+
+        assert!(ComplianceOptions::new_from_vec(&[String::from("#[match=all, count=2]")]).is_ok());
+    }
+
+    #[test]
+    fn test_parse_fcc_options_min_max() {
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[min=5, max=2]")]).unwrap_err();
+        assert!(matches!(err, ParseError::ConflictingOptions(_, _)));
+
+        let err = ComplianceOptions::new_from_vec(&[String::from("#[max=2, min=5]")]).unwrap_err();
+        assert!(matches!(err, ParseError::ConflictingOptions(_, _)));
+
+        assert!(ComplianceOptions::new_from_vec(&[String::from("#[min=2, max=2]")]).is_ok());
+    }
 }
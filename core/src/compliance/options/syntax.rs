@@ -0,0 +1,297 @@
+use super::error::ParseError;
+
+const OPTION_START: &str = "#[";
+const OPTION_END: char = ']';
+const OPTION_SEPARATOR: char = ',';
+const QUOTE: char = '"';
+const ESCAPE: char = '\\';
+/// Options whose unquoted argument is a comma-separated list, taking the rest of the line.
+const LIST_OPTIONS: [&str; 1] = ["tags"];
+
+/// Option of an option line, columns start at 1 on the `#`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RawOption {
+    pub(crate) name: String,
+    pub(crate) arg: Option<String>,
+    pub(crate) column: usize,
+    /// Column of the argument, the one of the name when there is none.
+    pub(crate) arg_column: usize,
+}
+
+impl RawOption {
+    /// Column the error is about, the argument for invalid arguments.
+    pub(crate) fn error_column(&self, err: &ParseError) -> usize {
+        match err {
+            ParseError::InvalidOptionArgument(_, _) | ParseError::InvalidRegex(_, _) => {
+                self.arg_column
+            }
+            _ => self.column,
+        }
+    }
+}
+
+struct OptionLexer {
+    chars: Vec<char>,
+    pos: usize,
+    /// Index of the closing bracket.
+    end: usize,
+}
+
+impl OptionLexer {
+    fn peek(&self) -> Option<char> {
+        (self.pos < self.end).then(|| self.chars[self.pos])
+    }
+
+    fn column(&self) -> usize {
+        self.pos + 1
+    }
+
+    fn error(&self, reason: &str) -> ParseError {
+        ParseError::InvalidSyntax(String::from(reason), self.column())
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            return Err(self.error("expected an option name"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    /// Text between quotes, `\"` and `\\` being the only escapes.
+    fn quoted(&mut self) -> Result<String, ParseError> {
+        let quote = self.column();
+        let mut arg = String::new();
+        self.pos += 1;
+
+        loop {
+            match self.peek() {
+                None => {
+                    return Err(ParseError::InvalidSyntax(
+                        String::from("unterminated quoted argument"),
+                        quote,
+                    ))
+                }
+                Some(QUOTE) => {
+                    self.pos += 1;
+                    return Ok(arg);
+                }
+                Some(ESCAPE) => {
+                    let escape = self.column();
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c @ (QUOTE | ESCAPE)) => arg.push(c),
+                        _ => {
+                            return Err(ParseError::InvalidSyntax(
+                                String::from("only \\\" and \\\\ can be escaped"),
+                                escape,
+                            ))
+                        }
+                    }
+                }
+                Some(c) => arg.push(c),
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Text up to the next separator, separators between brackets belonging to the argument.
+    /// A list takes every separator up to the end of the line.
+    fn bare(&mut self, list: bool) -> Result<String, ParseError> {
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth > 0 => depth -= 1,
+                OPTION_SEPARATOR if depth == 0 && !list => break,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+
+        let arg: String = self.chars[start..self.pos].iter().collect();
+        let arg = arg.trim_end();
+        if arg.is_empty() {
+            return Err(self.error("expected an argument after \"=\""));
+        }
+        Ok(String::from(arg))
+    }
+
+    fn option(&mut self) -> Result<RawOption, ParseError> {
+        self.skip_whitespaces();
+        let column = self.column();
+        let name = self.name()?;
+        self.skip_whitespaces();
+
+        let mut option = RawOption {
+            name,
+            arg: None,
+            column,
+            arg_column: column,
+        };
+        if self.peek() == Some('=') {
+            self.pos += 1;
+            self.skip_whitespaces();
+            option.arg_column = self.column();
+            option.arg = Some(match self.peek() {
+                Some(QUOTE) => self.quoted()?,
+                _ => self.bare(LIST_OPTIONS.contains(&option.name.as_str()))?,
+            });
+            self.skip_whitespaces();
+        }
+
+        Ok(option)
+    }
+}
+
+/// Options of a line like `#[flag, name=value, other="quoted, \"text\""]`.
+pub(crate) fn parse_option_line(line: &str) -> Result<Vec<RawOption>, ParseError> {
+    let chars: Vec<char> = line.trim().chars().collect();
+    let mut lexer = OptionLexer {
+        end: chars.len(),
+        chars,
+        pos: 0,
+    };
+
+    if !line.trim_start().starts_with(OPTION_START) {
+        return Err(ParseError::MalformedOption(String::from(line.trim())));
+    }
+    if lexer.end < OPTION_START.len() + 1 || lexer.chars[lexer.end - 1] != OPTION_END {
+        lexer.pos = lexer.end;
+        return Err(lexer.error("expected \"]\" at the end of the line"));
+    }
+    lexer.end -= 1;
+    lexer.pos = OPTION_START.len();
+
+    let mut options = vec![];
+    loop {
+        options.push(lexer.option()?);
+        match lexer.peek() {
+            None => return Ok(options),
+            Some(OPTION_SEPARATOR) => lexer.pos += 1,
+            Some(c) => {
+                return Err(lexer.error(&format!("unexpected \"{c}\", expected \",\" or \"]\"")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Vec<(String, Option<String>)> {
+        parse_option_line(line)
+            .unwrap()
+            .into_iter()
+            .map(|o| (o.name, o.arg))
+            .collect()
+    }
+
+    fn syntax_error(line: &str) -> (String, usize) {
+        match parse_option_line(line) {
+            Err(ParseError::InvalidSyntax(reason, column)) => (reason, column),
+            result => panic!("{result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_option_line() {
+        assert_eq!(parse("#[regex]"), [(String::from("regex"), None)]);
+        assert_eq!(
+            parse("  #[ state=absent , regex ]  "),
+            [
+                (String::from("state"), Some(String::from("absent"))),
+                (String::from("regex"), None)
+            ]
+        );
+        assert_eq!(
+            parse(r#"#[description="Telnet, \"in clear\" \\ text", id=SEC-001]"#),
+            [
+                (
+                    String::from("description"),
+                    Some(String::from(r#"Telnet, "in clear" \ text"#))
+                ),
+                (String::from("id"), Some(String::from("SEC-001")))
+            ]
+        );
+        // Separators inside brackets belong to the regex
+        assert_eq!(
+            parse("#[having=vlan [0-9]{1,3}(,[0-9]+)?, for-each]"),
+            [
+                (
+                    String::from("having"),
+                    Some(String::from("vlan [0-9]{1,3}(,[0-9]+)?"))
+                ),
+                (String::from("for-each"), None)
+            ]
+        );
+        // Unquoted lists take the rest of the line
+        assert_eq!(
+            parse("#[regex, tags=security, cis]"),
+            [
+                (String::from("regex"), None),
+                (String::from("tags"), Some(String::from("security, cis")))
+            ]
+        );
+        assert_eq!(
+            parse(r#"#[tags="security, cis", regex]"#),
+            [
+                (String::from("tags"), Some(String::from("security, cis"))),
+                (String::from("regex"), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_option_line_columns() {
+        let options = parse_option_line(r#"#[regex, when = "a b"]"#).unwrap();
+        assert_eq!((options[0].column, options[0].arg_column), (3, 3));
+        assert_eq!((options[1].column, options[1].arg_column), (10, 17));
+    }
+
+    #[test]
+    fn test_parse_option_line_errors() {
+        assert_eq!(
+            syntax_error("#[regex"),
+            (String::from("expected \"]\" at the end of the line"), 8)
+        );
+        assert_eq!(
+            syntax_error("#[regex,]"),
+            (String::from("expected an option name"), 9)
+        );
+        assert_eq!(
+            syntax_error("#[state=]"),
+            (String::from("expected an argument after \"=\""), 9)
+        );
+        assert_eq!(
+            syntax_error(r#"#[id="SEC-001]"#),
+            (String::from("unterminated quoted argument"), 6)
+        );
+        assert_eq!(
+            syntax_error(r#"#[id="SEC\001"]"#),
+            (String::from("only \\\" and \\\\ can be escaped"), 10)
+        );
+        assert_eq!(
+            syntax_error(r#"#[id="SEC" regex]"#),
+            (
+                String::from("unexpected \"r\", expected \",\" or \"]\""),
+                12
+            )
+        );
+    }
+}
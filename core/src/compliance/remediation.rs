@@ -0,0 +1,190 @@
+use std::fmt;
+
+use crate::config::FlatConfigItem;
+
+use super::{
+    misc::{ComplianceError, ComplianceOk},
+    options::{ComplianceOptionsContainer, StateOption},
+    report::{ComplianceReport, ComplianceReportNode},
+    typed::has_typed_placeholders,
+    variables::{bind_item, Variables},
+};
+
+const NEGATION_PREFIX: &str = "no ";
+const EXIT_COMMAND: &str = "exit";
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemediationCommand {
+    pub depth: usize,
+    pub command: String,
+}
+
+impl RemediationCommand {
+    pub fn new(depth: usize, command: &str) -> Self {
+        Self {
+            depth,
+            command: String::from(command),
+        }
+    }
+}
+
+impl fmt::Display for RemediationCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", " ".repeat(self.depth), self.command)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone)]
+pub struct Remediation {
+    pub commands: Vec<RemediationCommand>,
+    /// Policy items that can't be turned into commands (missing regex, out of order, count).
+    pub skipped: Vec<FlatConfigItem>,
+}
+
+impl Remediation {
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn push(&mut self, depth: usize, command: &str) {
+        self.commands.push(RemediationCommand::new(depth, command));
+    }
+
+    fn add_nodes(&mut self, nodes: &[ComplianceReportNode], depth: usize) {
+        for node in nodes {
+            match node.result.result {
+                Ok(ComplianceOk::IsPresent(ref item)) => self.add_block(item, node, depth),
+                Ok(_) => {}
+                Err(ComplianceError::ShouldBePresentIsAbsent) => {
+                    self.add_policy_item(&node.result.policy, &node.result.bindings, depth);
+                }
+                Err(ComplianceError::ShouldBeAbsentIsPresent(ref item)) => {
+                    self.negate_under(item, &node.result.path, depth);
+                }
+                Err(ComplianceError::UnexpectedItem(ref item)) => {
+                    self.push(depth, &negate(item.get_item_key()));
+                }
+                Err(ComplianceError::CountMismatch { .. })
+                | Err(ComplianceError::ConstraintViolation { .. })
+                | Err(ComplianceError::InvalidPattern { .. }) => {
+                    // Which lines to add or remove is up to the user
+                    self.skipped.push(node.result.policy.clone());
+                }
+                Err(ComplianceError::OutOfOrder { ref item, .. }) => {
+                    // Moving a line is not a command
+                    self.skipped.push(node.result.policy.clone());
+                    self.add_block(item, node, depth);
+                }
+            }
+        }
+    }
+
+    /// Enter the configuration block to fix its children.
+    fn add_block(&mut self, item: &FlatConfigItem, node: &ComplianceReportNode, depth: usize) {
+        if node.children.iter().all(|c| c.summary.is_compliant()) {
+            return;
+        }
+
+        self.push(depth, item.get_item_key());
+        self.add_nodes(&node.children, depth + 1);
+        self.push(depth + 1, EXIT_COMMAND);
+    }
+
+    /// Negate an item found in descendants, entering the blocks above it.
+    fn negate_under(&mut self, item: &FlatConfigItem, path: &[String], depth: usize) {
+        for (i, key) in path.iter().enumerate() {
+            self.push(depth + i, key);
+        }
+        self.push(depth + path.len(), &negate(item.get_item_key()));
+        for i in (0..path.len()).rev() {
+            self.push(depth + i + 1, EXIT_COMMAND);
+        }
+    }
+
+    fn add_policy_item(&mut self, item: &FlatConfigItem, bindings: &Variables, depth: usize) {
+        if item.get_options().match_kind.is_pattern() || has_typed_placeholders(item.get_item_key())
+        {
+            self.skipped.push(item.clone());
+            return;
+        }
+
+        self.push(depth, item.get_item_key());
+
+        if let FlatConfigItem::Parent(parent) = item {
+            for child in &parent.items {
+                if matches!(child.get_options().state, StateOption::Present) {
+                    self.add_policy_item(&bind_item(child, bindings), bindings, depth + 1);
+                }
+            }
+            self.push(depth + 1, EXIT_COMMAND);
+        }
+    }
+}
+
+impl fmt::Display for Remediation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for command in &self.commands {
+            writeln!(f, "{command}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Negate a configuration line, removing the prefix if the line is already a negation.
+pub fn negate(line: &str) -> String {
+    match line.strip_prefix(NEGATION_PREFIX) {
+        Some(line) => String::from(line.trim_start()),
+        None => format!("{NEGATION_PREFIX}{line}"),
+    }
+}
+
+/// Build the ordered command set which would fix every failed result of the report.
+pub fn remediate(report: &ComplianceReport) -> Remediation {
+    let mut remediation = Remediation::default();
+    remediation.add_nodes(&report.results, 0);
+    remediation
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{compliance::check_compliance, FlatConfig, FlatConfigCompliance};
+
+    use super::*;
+
+    #[test]
+    fn test_negate() {
+        assert_eq!(negate("ip http server"), "no ip http server");
+        assert_eq!(negate("no ip http server"), "ip http server");
+    }
+
+    #[test]
+    fn test_remediate() {
+        let policy =
+            FlatConfigCompliance::from_str(include_str!("../../test/remediation/1_p.txt")).unwrap();
+        let config = FlatConfig::from_str(include_str!("../../test/remediation/1_c.txt")).unwrap();
+
+        let remediation = remediate(&check_compliance(policy, config));
+
+        assert_eq!(
+            remediation.to_string(),
+            include_str!("../../test/remediation/1_r.txt")
+        );
+        assert_eq!(remediation.skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_remediate_compliant() {
+        let policy =
+            FlatConfigCompliance::from_str(include_str!("../../test/remediation/1_c.txt")).unwrap();
+        let config = FlatConfig::from_str(include_str!("../../test/remediation/1_c.txt")).unwrap();
+
+        let remediation = remediate(&check_compliance(policy, config));
+
+        assert!(remediation.is_empty());
+        assert!(remediation.skipped.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use std::{fmt, slice::Iter};
+
+use super::{
+    misc::{ComplianceError, ComplianceOk, ItemComplianceResult},
+    options::Severity,
+};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ComplianceSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub optional: usize,
+    pub not_applicable: usize,
+}
+
+impl ComplianceSummary {
+    fn from_result(result: &ItemComplianceResult) -> Self {
+        match result.result {
+            Ok(ComplianceOk::OptionalIsAbsent) => Self {
+                optional: 1,
+                ..Default::default()
+            },
+            Ok(ComplianceOk::NotApplicable) => Self {
+                not_applicable: 1,
+                ..Default::default()
+            },
+            Ok(_) => Self {
+                passed: 1,
+                ..Default::default()
+            },
+            Err(_) => Self {
+                failed: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn add(&mut self, other: &Self) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.optional += other.optional;
+        self.not_applicable += other.not_applicable;
+    }
+
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.optional + self.not_applicable
+    }
+
+    pub fn is_compliant(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+impl fmt::Display for ComplianceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} optional, {} not applicable",
+            self.passed, self.failed, self.optional, self.not_applicable
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub struct ComplianceReportNode {
+    pub result: ItemComplianceResult,
+    /// Results of the policy children, checked inside the matching configuration block.
+    pub children: Vec<ComplianceReportNode>,
+    /// Counts of this node and all its descendants.
+    pub summary: ComplianceSummary,
+}
+
+impl ComplianceReportNode {
+    pub(crate) fn new(result: ItemComplianceResult, children: Vec<ComplianceReportNode>) -> Self {
+        let mut summary = ComplianceSummary::from_result(&result);
+        for child in &children {
+            summary.add(&child.summary);
+        }
+
+        Self {
+            result,
+            children,
+            summary,
+        }
+    }
+
+    /// Key of the configuration block children were checked in, policy key otherwise.
+    pub fn get_key(&self) -> &str {
+        match self.result.result {
+            Ok(ComplianceOk::IsPresent(ref item))
+            | Err(ComplianceError::OutOfOrder { ref item, .. }) => item.get_item_key(),
+            _ => self.result.policy.get_item_key(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone)]
+pub struct ComplianceReport {
+    pub policy: Option<String>,
+    pub configuration: Option<String>,
+    pub results: Vec<ComplianceReportNode>,
+    pub summary: ComplianceSummary,
+}
+
+impl ComplianceReport {
+    pub(crate) fn new(
+        policy: Option<String>,
+        configuration: Option<String>,
+        results: Vec<ComplianceReportNode>,
+    ) -> Self {
+        let mut summary = ComplianceSummary::default();
+        for node in &results {
+            summary.add(&node.summary);
+        }
+
+        Self {
+            policy,
+            configuration,
+            results,
+            summary,
+        }
+    }
+
+    pub fn is_compliant(&self) -> bool {
+        self.summary.is_compliant()
+    }
+
+    /// No failed result is rated `severity` or more severe, unrated failures are ignored.
+    pub fn is_compliant_at(&self, severity: Severity) -> bool {
+        !self
+            .failures()
+            .any(|r| r.severity.is_some_and(|s| s >= severity))
+    }
+
+    /// Every result, depth first, in policy order.
+    pub fn iter(&self) -> impl Iterator<Item = &ItemComplianceResult> {
+        self.flatten().map(|(_, r)| r)
+    }
+
+    /// Failed results only, depth first.
+    pub fn failures(&self) -> impl Iterator<Item = &ItemComplianceResult> {
+        self.iter().filter(|r| r.result.is_err())
+    }
+
+    /// Every result along with the keys of the configuration blocks it was checked in.
+    pub fn flatten(&self) -> ComplianceReportIter<'_> {
+        ComplianceReportIter {
+            stack: vec![self.results.iter()],
+            path: vec![],
+        }
+    }
+}
+
+impl fmt::Display for ComplianceReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (path, result) in self.flatten() {
+            writeln!(f, "{}{}", " ".repeat(path.len()), result)?;
+        }
+        write!(f, "{}", self.summary)
+    }
+}
+
+pub struct ComplianceReportIter<'a> {
+    stack: Vec<Iter<'a, ComplianceReportNode>>,
+    path: Vec<&'a str>,
+}
+
+impl<'a> Iterator for ComplianceReportIter<'a> {
+    type Item = (Vec<&'a str>, &'a ItemComplianceResult);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let nodes = self.stack.last_mut()?;
+            match nodes.next() {
+                Some(node) => {
+                    let item = (self.path.clone(), &node.result);
+                    if !node.children.is_empty() {
+                        self.path.push(node.get_key());
+                        self.stack.push(node.children.iter());
+                    }
+                    return Some(item);
+                }
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{compliance::check_compliance, FlatConfig, FlatConfigCompliance};
+
+    #[test]
+    fn test_compliance_report_tree() {
+        let policy = FlatConfigCompliance::from_str(include_str!(
+            "../../test/process_parent_compliance_check/1_p.txt"
+        ))
+        .unwrap();
+        let config = FlatConfig::from_str(include_str!(
+            "../../test/process_parent_compliance_check/1_c.txt"
+        ))
+        .unwrap();
+        let report = check_compliance(policy, config);
+
+        assert_eq!(report.results.len(), 4);
+        assert_eq!(report.results[1].children.len(), 1);
+        assert_eq!(report.results[1].summary.passed, 2);
+        assert_eq!(report.summary.passed, 4);
+        assert_eq!(report.summary.optional, 1);
+        assert!(report.is_compliant());
+        assert_eq!(report.failures().count(), 0);
+
+        let flatten: Vec<_> = report.flatten().collect();
+        assert_eq!(flatten.len(), 5);
+        assert!(flatten[0].0.is_empty());
+        assert_eq!(flatten[2].0, vec!["line2"]);
+        assert!(flatten[3].0.is_empty());
+    }
+
+    #[test]
+    fn test_compliance_report_failures() {
+        let policy = FlatConfigCompliance::from_str(include_str!(
+            "../../test/process_parent_compliance_check/3_p.txt"
+        ))
+        .unwrap()
+        .with_source("3_p.txt");
+        let config = FlatConfig::from_str(include_str!(
+            "../../test/process_parent_compliance_check/3_c.txt"
+        ))
+        .unwrap()
+        .with_source("3_c.txt");
+        let report = check_compliance(policy, config);
+
+        assert_eq!(report.policy.as_deref(), Some("3_p.txt"));
+        assert_eq!(report.configuration.as_deref(), Some("3_c.txt"));
+        assert!(!report.is_compliant());
+        assert_eq!(report.summary.failed, 3);
+        assert_eq!(report.failures().count(), 3);
+    }
+}
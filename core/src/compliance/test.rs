@@ -1,8 +1,22 @@
 use std::str::FromStr;
 
-use crate::{compliance::misc::ComplianceError, config::FlatConfig};
+use crate::{
+    compliance::misc::{ComplianceError, ComplianceOk},
+    config::FlatConfig,
+    parse::misc::ParseOption,
+};
 
-use super::{process_parent_compliance_check, FlatConfigCompliance};
+use super::{
+    check_compliance, process_parent_compliance_check, remediation::remediate, ComplianceReport,
+    ComplianceReportNode, FlatConfigCompliance, ItemComplianceResult, Severity, Variables,
+};
+
+fn flatten(nodes: Vec<ComplianceReportNode>) -> Vec<ItemComplianceResult> {
+    ComplianceReport::new(None, None, nodes)
+        .iter()
+        .cloned()
+        .collect()
+}
 
 #[test]
 fn test_process_parent_compliance_check_ok() {
@@ -14,7 +28,7 @@ fn test_process_parent_compliance_check_ok() {
         "../../test/process_parent_compliance_check/1_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 5);
     for r in result {
@@ -32,7 +46,7 @@ fn test_process_parent_compliance_check_present_is_absent() {
         "../../test/process_parent_compliance_check/2_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 2);
     for r in result {
@@ -53,7 +67,7 @@ fn test_process_parent_compliance_check_absent_is_present() {
         "../../test/process_parent_compliance_check/3_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 3);
     for r in result {
@@ -74,7 +88,7 @@ fn test_process_parent_compliance_check_absent_ignore_enum_variant() {
         "../../test/process_parent_compliance_check/1_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 2);
     for r in result {
@@ -95,7 +109,7 @@ fn test_process_parent_compliance_check_optional() {
         "../../test/process_parent_compliance_check/5_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 3);
     for r in result {
@@ -113,7 +127,7 @@ fn test_process_parent_compliance_check_regex_match_all() {
         "../../test/process_parent_compliance_check/6_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 4);
     for r in result {
@@ -131,7 +145,7 @@ fn test_process_parent_compliance_check_regex_match_first() {
         "../../test/process_parent_compliance_check/6_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 1);
     assert!(result[0].result.is_ok())
@@ -147,7 +161,7 @@ fn test_process_parent_compliance_check_item_can_match_once_only() {
         "../../test/process_parent_compliance_check/8_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 3);
     for r in result {
@@ -165,7 +179,7 @@ fn test_process_parent_compliance_check_item_match_all() {
         "../../test/process_parent_compliance_check/8_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 4);
     for r in result {
@@ -183,7 +197,7 @@ fn test_process_parent_compliance_check_absent_match_all() {
         "../../test/process_parent_compliance_check/8_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 4);
     assert!(result[0].result.is_err());
@@ -204,7 +218,7 @@ fn test_process_parent_compliance_check_absent_match_first() {
         "../../test/process_parent_compliance_check/8_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 3);
     assert!(result[0].result.is_err());
@@ -224,10 +238,404 @@ fn test_process_parent_compliance_remove_first_already_match_item_when_identical
         "../../test/process_parent_compliance_check/8_c.txt"
     ))
     .unwrap();
-    let result = process_parent_compliance_check(&policy, &config);
+    let result = flatten(process_parent_compliance_check(&policy, &config));
 
     assert_eq!(result.len(), 2);
     for r in result {
         assert!(r.result.is_ok())
     }
 }
+
+#[test]
+fn test_process_parent_compliance_check_exact() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/13_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/13_c.txt"
+    ))
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 7);
+    let failures: Vec<_> = result.iter().filter(|r| r.result.is_err()).collect();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        failures[0].policy.get_item_key(),
+        "ip access-list standard MGMT"
+    );
+    let Err(ComplianceError::UnexpectedItem(ref item)) = failures[0].result else {
+        panic!()
+    };
+    assert_eq!(item.get_item_key(), "permit any");
+}
+
+#[test]
+fn test_process_parent_compliance_check_order_strict() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/14_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/14_c.txt"
+    ))
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 3);
+    assert!(result[0].result.is_ok());
+    assert!(matches!(
+        result[1].result,
+        Err(ComplianceError::OutOfOrder {
+            expected: 1,
+            found: 2,
+            ..
+        })
+    ));
+    assert!(matches!(
+        result[2].result,
+        Err(ComplianceError::OutOfOrder {
+            expected: 2,
+            found: 1,
+            ..
+        })
+    ));
+
+    let policy = FlatConfigCompliance::from_str(
+        "#[order=strict]\nip access-list extended EDGE\n permit tcp any any eq 22\n permit tcp any any eq 443\n",
+    )
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+    assert_eq!(result.len(), 3);
+    for r in result {
+        assert!(r.result.is_ok())
+    }
+}
+
+#[test]
+fn test_process_parent_compliance_check_order_relative() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/15_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/14_c.txt"
+    ))
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 4);
+    assert!(result[1].result.is_ok());
+    assert!(result[2].result.is_ok());
+    let Err(ComplianceError::OutOfOrder {
+        ref item,
+        expected,
+        found,
+    }) = result[3].result
+    else {
+        panic!()
+    };
+    assert_eq!(item.get_item_key(), "permit tcp any any eq 443");
+    assert_eq!((expected, found), (5, 2));
+}
+
+#[test]
+fn test_process_parent_compliance_check_count() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/16_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/16_c.txt"
+    ))
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 5);
+    let Err(ComplianceError::CountMismatch {
+        ref expected,
+        ref found,
+    }) = result[0].result
+    else {
+        panic!()
+    };
+    assert_eq!((expected.min, expected.max), (Some(2), Some(2)));
+    assert_eq!(found.len(), 3);
+
+    assert!(result[1].result.is_ok());
+    assert!(result[2].result.is_ok());
+    assert!(matches!(
+        result[3].result,
+        Err(ComplianceError::CountMismatch { ref found, .. }) if found.is_empty()
+    ));
+    assert!(matches!(
+        result[4].result,
+        Ok(ComplianceOk::OptionalIsAbsent)
+    ));
+}
+
+#[test]
+fn test_process_parent_compliance_check_bindings() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/17_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/17_c.txt"
+    ))
+    .unwrap();
+    // Captured values are not variables
+    let policy = policy.with_variables(&Variables::new()).unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 11);
+    assert_eq!(result[0].bindings["vlan"], "10");
+    assert_eq!(result[1].policy.get_item_key(), "description SVI 10");
+    assert!(result[1].result.is_ok());
+    assert!(result[5].result.is_ok());
+
+    let vlans: Vec<_> = result[6..9]
+        .iter()
+        .map(|r| (r.policy.get_item_key(), r.result.is_ok()))
+        .collect();
+    assert_eq!(
+        vlans,
+        [("vlan 10", true), ("vlan 20", true), ("vlan 30", false)]
+    );
+    assert_eq!(result[8].bindings["vlan"], "30");
+
+    assert_eq!(result[9].bindings["id"], "0");
+    assert!(matches!(
+        result[10].result,
+        Err(ComplianceError::ShouldBePresentIsAbsent)
+    ));
+}
+
+#[test]
+fn test_process_parent_compliance_check_bindings_without_match() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/17_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str("vlan 10\n").unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    // Nothing to check for VLANs without SVI
+    assert_eq!(result.len(), 2);
+    assert!(result
+        .iter()
+        .all(|r| r.policy.get_item_key() != "vlan {{vlan}}"));
+}
+
+#[test]
+fn test_process_parent_compliance_check_when() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/18_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/18_c.txt"
+    ))
+    .unwrap();
+    let report = ComplianceReport::new(
+        None,
+        None,
+        process_parent_compliance_check(&policy, &config),
+    );
+
+    assert_eq!(report.summary.not_applicable, 2);
+    assert_eq!(report.summary.failed, 1);
+    assert_eq!(report.summary.total(), 9);
+
+    let result: Vec<_> = report.iter().collect();
+    assert!(matches!(result[1].result, Ok(ComplianceOk::IsPresent(_))));
+    assert!(matches!(result[2].result, Ok(ComplianceOk::NotApplicable)));
+    assert!(matches!(result[6].result, Ok(ComplianceOk::NotApplicable)));
+    assert!(matches!(
+        result[8].result,
+        Err(ComplianceError::ShouldBePresentIsAbsent)
+    ));
+}
+
+#[test]
+fn test_process_parent_compliance_check_for_each() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/19_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/19_c.txt"
+    ))
+    .unwrap();
+    let results = process_parent_compliance_check(&policy, &config);
+
+    // One node per selected block, trunk port is not selected
+    let blocks: Vec<_> = results.iter().map(|n| n.get_key()).collect();
+    assert_eq!(
+        blocks,
+        [
+            "interface GigabitEthernet0/1",
+            "interface GigabitEthernet0/3",
+            "interface Tunnel.*"
+        ]
+    );
+    assert!(results[0].summary.is_compliant());
+    assert_eq!(results[1].summary.failed, 1);
+    assert!(matches!(
+        results[2].result.result,
+        Ok(ComplianceOk::OptionalIsAbsent)
+    ));
+}
+
+#[test]
+fn test_process_parent_compliance_check_match_modes() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/20_p.txt"
+    ))
+    .unwrap();
+    let raw_config = include_str!("../../test/process_parent_compliance_check/20_c.txt");
+
+    let config = FlatConfig::from_str(raw_config).unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+    assert_eq!(result.len(), 4);
+    assert!(result[..3].iter().all(|r| r.result.is_ok()));
+    // No match mode on the hostname
+    assert!(result[3].result.is_err());
+
+    let config = FlatConfig::from_str_with_options(
+        raw_config,
+        ParseOption {
+            normalize_whitespace: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+    assert!(result.iter().all(|r| r.result.is_ok()));
+}
+
+#[test]
+fn test_process_parent_compliance_check_typed_placeholders() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/21_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/21_c.txt"
+    ))
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 4);
+    assert!(matches!(result[0].result, Ok(ComplianceOk::IsPresent(_))));
+    let Err(ComplianceError::ConstraintViolation {
+        ref item,
+        ref value,
+        ref constraint,
+    }) = result[1].result
+    else {
+        panic!()
+    };
+    assert_eq!(item.get_item_key(), "logging host 192.0.2.10");
+    assert_eq!(value, "192.0.2.10");
+    assert_eq!(constraint, "ip in 10.10.0.0/16");
+    assert!(result[2].result.is_ok());
+    assert!(result[3].result.is_ok());
+
+    assert!(FlatConfigCompliance::from_str("logging host {{ :mac }}").is_err());
+
+    // Placeholder in an optional group that did not match
+    let policy = FlatConfigCompliance::from_str("#[regex]\nlogging host( {{ :ip }})?\n").unwrap();
+    let config = FlatConfig::from_str("logging host\n").unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+    assert!(matches!(result[0].result, Ok(ComplianceOk::IsPresent(_))));
+}
+
+#[test]
+fn test_process_parent_compliance_check_numeric_placeholders() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/22_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/22_c.txt"
+    ))
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 5);
+    assert!(result[..4].iter().all(|r| r.result.is_ok()));
+    assert_eq!(
+        result[4].to_string(),
+        "Policy() \"exec-timeout {{ :int <= 10 }} {{ :int }}\" found \"exec-timeout 30 0\" but \"30\" is not int <= 10."
+    );
+}
+
+#[test]
+fn test_process_parent_compliance_check_descendants_scope() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/23_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/23_c.txt"
+    ))
+    .unwrap();
+    let result = flatten(process_parent_compliance_check(&policy, &config));
+
+    assert_eq!(result.len(), 4);
+    assert!(matches!(
+        result[0].result,
+        Err(ComplianceError::ShouldBeAbsentIsPresent(_))
+    ));
+    assert!(result[0].path.is_empty());
+    assert_eq!(result[1].path, ["line vty 0 4"]);
+    assert_eq!(
+        result[2].to_string(),
+        "Policy(state: Absent,scope=Descendants,match=All) \"shutdown\" found something that should not be there: \"shutdown\" under \"interface GigabitEthernet0/2\""
+    );
+    assert!(matches!(result[3].result, Ok(ComplianceOk::IsPresent(_))));
+    assert!(result[3].path.is_empty());
+
+    let remediation = remediate(&check_compliance(policy, config));
+    assert_eq!(
+        remediation.to_string(),
+        "no username admin password 7 0822455D0A16\nline vty 0 4\n no password 7 045802150C2E\n exit\ninterface GigabitEthernet0/2\n no shutdown\n exit\n"
+    );
+}
+
+#[test]
+fn test_process_parent_compliance_check_severity() {
+    let policy = FlatConfigCompliance::from_str(include_str!(
+        "../../test/process_parent_compliance_check/24_p.txt"
+    ))
+    .unwrap();
+    let config = FlatConfig::from_str(include_str!(
+        "../../test/process_parent_compliance_check/24_c.txt"
+    ))
+    .unwrap();
+    let report = check_compliance(policy, config);
+    let result: Vec<&ItemComplianceResult> = report.iter().collect();
+
+    assert_eq!(result.len(), 8);
+    assert_eq!(result[0].id.as_deref(), Some("SEC-001"));
+    assert_eq!(result[0].severity, Some(Severity::Critical));
+    assert_eq!(
+        result[0].description.as_deref(),
+        Some("Telnet sends passwords in clear text")
+    );
+    assert_eq!(result[0].tags, ["security", "cis"]);
+    // Children are rated as their block unless they have their own severity
+    assert_eq!(result[3].severity, Some(Severity::Low));
+    assert_eq!(
+        result[5].to_string(),
+        "Policy(severity=High,) \"no ip proxy-arp\" no match found."
+    );
+    assert_eq!(result[7].id.as_deref(), Some("LOG-001"));
+    assert_eq!(result[7].severity, None);
+
+    assert!(!report.is_compliant());
+    assert!(!report.is_compliant_at(Severity::High));
+    assert!(report.is_compliant_at(Severity::Critical));
+}
@@ -0,0 +1,381 @@
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+    ops::Range,
+    str::FromStr,
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use strum::{EnumString, IntoStaticStr};
+
+use super::options::error::ParseError;
+
+lazy_static! {
+    static ref TYPED_PLACEHOLDER_REGEX: Regex = Regex::new(
+        r"\{\{\s*(?<name>[A-Za-z_][\w.-]*)?\s*:\s*(?<type>[\w-]+)\s*(?<constraint>[^}]*?)\s*\}\}"
+    )
+    .unwrap();
+}
+
+/// Kind of value a typed placeholder accepts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ValueType {
+    /// IPv4 or IPv6 address
+    Ip,
+    Ipv4,
+    Ipv6,
+    /// IPv4 or IPv6 prefix, `address/length`
+    Prefix,
+    Ipv4Prefix,
+    Ipv6Prefix,
+    /// Contiguous IPv4 mask, `255.255.255.0`
+    Netmask,
+    /// Inverse of a contiguous IPv4 mask, `0.0.0.255`
+    Wildcard,
+    /// Signed integer
+    Int,
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", <&str>::from(self))
+    }
+}
+
+/// IPv4 or IPv6 network, host bits of the address are allowed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IpNetwork {
+    pub addr: IpAddr,
+    pub len: u8,
+}
+
+impl IpNetwork {
+    fn bits(addr: IpAddr) -> (u128, u8) {
+        match addr {
+            IpAddr::V4(addr) => (u128::from(u32::from(addr)), 32),
+            IpAddr::V6(addr) => (u128::from(addr), 128),
+        }
+    }
+
+    pub fn contains_addr(&self, addr: IpAddr) -> bool {
+        let (network, width) = Self::bits(self.addr);
+        let (addr, addr_width) = Self::bits(addr);
+        if width != addr_width {
+            return false;
+        }
+
+        let host_bits = u32::from(width - self.len);
+        let mask = u128::MAX.checked_shl(host_bits).unwrap_or(0);
+        network & mask == addr & mask
+    }
+
+    pub fn contains(&self, other: &IpNetwork) -> bool {
+        other.len >= self.len && self.contains_addr(other.addr)
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((addr, len)) = s.split_once('/') else {
+            return Err(format!("\"{s}\" is not a prefix"));
+        };
+        let addr = IpAddr::from_str(addr).map_err(|err| format!("\"{addr}\": {err}"))?;
+        let len = u8::from_str(len).map_err(|err| format!("\"{len}\": {err}"))?;
+        if len > Self::bits(addr).1 {
+            return Err(format!("prefix length {len} out of range"));
+        }
+        Ok(Self { addr, len })
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, IntoStaticStr)]
+pub enum Comparison {
+    #[strum(serialize = "<")]
+    Lt,
+    #[strum(serialize = "<=")]
+    Le,
+    #[strum(serialize = ">")]
+    Gt,
+    #[strum(serialize = ">=")]
+    Ge,
+    #[strum(serialize = "=")]
+    Eq,
+}
+
+impl Comparison {
+    fn holds(&self, value: i64, other: i64) -> bool {
+        match self {
+            Self::Lt => value < other,
+            Self::Le => value <= other,
+            Self::Gt => value > other,
+            Self::Ge => value >= other,
+            Self::Eq => value == other,
+        }
+    }
+}
+
+/// Condition on the value of a typed placeholder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    /// Address or prefix inside a network
+    In(IpNetwork),
+    /// Number compared to a bound
+    Compare(Comparison, i64),
+    /// Number between two bounds, both included
+    Range(i64, i64),
+}
+
+impl Constraint {
+    fn parse(value_type: ValueType, constraint: &str) -> Result<Option<Self>, String> {
+        let parse_int =
+            |s: &str| i64::from_str(s.trim()).map_err(|err| format!("\"{}\": {err}", s.trim()));
+
+        let constraint = match value_type {
+            _ if constraint.is_empty() => return Ok(None),
+            ValueType::Int => match constraint.split_once("..") {
+                Some((min, max)) => {
+                    let (min, max) = (parse_int(min)?, parse_int(max)?);
+                    if min > max {
+                        return Err(format!("empty range \"{constraint}\""));
+                    }
+                    Self::Range(min, max)
+                }
+                None => {
+                    // Longest operators first
+                    let Some(op) = ["<=", ">=", "<", ">", "="]
+                        .into_iter()
+                        .find(|op| constraint.starts_with(op))
+                    else {
+                        return Err(format!("unknown constraint \"{constraint}\""));
+                    };
+                    let bound = parse_int(&constraint[op.len()..])?;
+                    Self::Compare(Comparison::from_str(op).unwrap(), bound)
+                }
+            },
+            ValueType::Netmask | ValueType::Wildcard => {
+                return Err(format!("{value_type} can't have a constraint"))
+            }
+            _ => match constraint.split_once(char::is_whitespace) {
+                Some(("in", network)) => Self::In(IpNetwork::from_str(network.trim())?),
+                _ => return Err(format!("unknown constraint \"{constraint}\"")),
+            },
+        };
+        Ok(Some(constraint))
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::In(network) => write!(f, "in {network}"),
+            Self::Compare(op, bound) => write!(f, "{} {bound}", <&str>::from(op)),
+            Self::Range(min, max) => write!(f, "{min}..{max}"),
+        }
+    }
+}
+
+/// `{{ name:type constraint }}` placeholder of a policy line, matching one word of a
+/// configuration line. The name, if any, binds the word like a regex named group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedPlaceholder {
+    pub name: Option<String>,
+    pub value_type: ValueType,
+    pub constraint: Option<Constraint>,
+}
+
+impl TypedPlaceholder {
+    fn new(name: Option<&str>, value_type: &str, constraint: &str) -> Result<Self, String> {
+        let value_type = ValueType::from_str(value_type)
+            .map_err(|_| format!("unknown placeholder type \"{value_type}\""))?;
+
+        let constraint = Constraint::parse(value_type, constraint)?;
+
+        Ok(Self {
+            name: name.map(String::from),
+            value_type,
+            constraint,
+        })
+    }
+
+    /// Whether a configuration word is a valid value satisfying the constraint.
+    pub fn check(&self, value: &str) -> bool {
+        let network = match self.value_type {
+            ValueType::Int => {
+                let Ok(value) = i64::from_str(value) else {
+                    return false;
+                };
+                return match self.constraint {
+                    Some(Constraint::Compare(op, bound)) => op.holds(value, bound),
+                    Some(Constraint::Range(min, max)) => (min..=max).contains(&value),
+                    _ => true,
+                };
+            }
+            ValueType::Ip | ValueType::Ipv4 | ValueType::Ipv6 => {
+                let Ok(addr) = IpAddr::from_str(value) else {
+                    return false;
+                };
+                match (self.value_type, addr) {
+                    (ValueType::Ipv4, IpAddr::V6(_)) | (ValueType::Ipv6, IpAddr::V4(_)) => {
+                        return false
+                    }
+                    _ => IpNetwork {
+                        addr,
+                        len: IpNetwork::bits(addr).1,
+                    },
+                }
+            }
+            ValueType::Prefix | ValueType::Ipv4Prefix | ValueType::Ipv6Prefix => {
+                let Ok(network) = IpNetwork::from_str(value) else {
+                    return false;
+                };
+                match (self.value_type, network.addr) {
+                    (ValueType::Ipv4Prefix, IpAddr::V6(_))
+                    | (ValueType::Ipv6Prefix, IpAddr::V4(_)) => return false,
+                    _ => network,
+                }
+            }
+            ValueType::Netmask => {
+                return Ipv4Addr::from_str(value).is_ok_and(|mask| {
+                    u32::from(mask).leading_ones() + u32::from(mask).trailing_zeros() == 32
+                });
+            }
+            ValueType::Wildcard => {
+                return Ipv4Addr::from_str(value).is_ok_and(|mask| {
+                    u32::from(mask).leading_zeros() + u32::from(mask).trailing_ones() == 32
+                });
+            }
+        };
+
+        match self.constraint {
+            Some(Constraint::In(ref supernet)) => supernet.contains(&network),
+            _ => true,
+        }
+    }
+}
+
+/// Value type and constraint, without the name.
+impl fmt::Display for TypedPlaceholder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value_type)?;
+        if let Some(ref constraint) = self.constraint {
+            write!(f, " {constraint}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Typed placeholders of a policy key, with their position in the key.
+pub(crate) fn typed_placeholders(
+    key: &str,
+) -> Result<Vec<(Range<usize>, TypedPlaceholder)>, ParseError> {
+    TYPED_PLACEHOLDER_REGEX
+        .captures_iter(key)
+        .map(|caps| {
+            let placeholder = caps.get(0).unwrap();
+            TypedPlaceholder::new(
+                caps.name("name").map(|n| n.as_str()),
+                &caps["type"],
+                &caps["constraint"],
+            )
+            .map(|typed| (placeholder.range(), typed))
+            .map_err(|reason| {
+                ParseError::InvalidPlaceholder(String::from(placeholder.as_str()), reason)
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn has_typed_placeholders(key: &str) -> bool {
+    TYPED_PLACEHOLDER_REGEX.is_match(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholder(s: &str) -> TypedPlaceholder {
+        let mut placeholders = typed_placeholders(s).unwrap();
+        assert_eq!(placeholders.len(), 1);
+        placeholders.remove(0).1
+    }
+
+    #[test]
+    fn test_typed_placeholder_addresses() {
+        let typed = placeholder("{{ :ipv4 in 10.0.0.0/8 }}");
+        assert_eq!(typed.name, None);
+        assert_eq!(typed.to_string(), "ipv4 in 10.0.0.0/8");
+        assert!(typed.check("10.1.2.3"));
+        assert!(!typed.check("192.168.1.1"));
+        assert!(!typed.check("10.1.2"));
+
+        let typed = placeholder("{{host:ip}}");
+        assert_eq!(typed.name.as_deref(), Some("host"));
+        assert!(typed.check("2001:db8::1"));
+        assert!(typed.check("192.0.2.1"));
+
+        let typed = placeholder("{{ :ipv6 in 2001:db8::/32 }}");
+        assert!(typed.check("2001:db8:1::1"));
+        assert!(!typed.check("2001:db9::1"));
+        assert!(!typed.check("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_typed_placeholder_prefixes_and_masks() {
+        let typed = placeholder("{{ :prefix in 10.0.0.0/8 }}");
+        assert!(typed.check("10.1.0.0/16"));
+        assert!(!typed.check("0.0.0.0/0"));
+        assert!(!typed.check("10.1.0.0"));
+
+        let typed = placeholder("{{ :netmask }}");
+        assert!(typed.check("255.255.255.0"));
+        assert!(typed.check("0.0.0.0"));
+        assert!(!typed.check("255.0.255.0"));
+
+        let typed = placeholder("{{ :wildcard }}");
+        assert!(typed.check("0.0.0.255"));
+        assert!(!typed.check("255.255.255.0"));
+    }
+
+    #[test]
+    fn test_typed_placeholder_numbers() {
+        let typed = placeholder("{{ :int <= 10 }}");
+        assert_eq!(typed.to_string(), "int <= 10");
+        assert!(typed.check("10"));
+        assert!(!typed.check("15"));
+        assert!(!typed.check("ten"));
+
+        let typed = placeholder("{{ size:int >=64000 }}");
+        assert_eq!(typed.name.as_deref(), Some("size"));
+        assert!(typed.check("64000"));
+        assert!(!typed.check("4096"));
+
+        let typed = placeholder("{{ :int 1500..9216 }}");
+        assert_eq!(typed.to_string(), "int 1500..9216");
+        assert!(typed.check("9216"));
+        assert!(!typed.check("9217"));
+
+        assert!(placeholder("{{ :int }}").check("-1"));
+    }
+
+    #[test]
+    fn test_typed_placeholder_errors() {
+        assert!(typed_placeholders("{{ :mac }}").is_err());
+        assert!(typed_placeholders("{{ :ipv4 in 10.0.0.0/33 }}").is_err());
+        assert!(typed_placeholders("{{ :ipv4 near 10.0.0.0/8 }}").is_err());
+        assert!(typed_placeholders("{{ :netmask in 10.0.0.0/8 }}").is_err());
+        assert!(typed_placeholders("{{ :int in 10.0.0.0/8 }}").is_err());
+        assert!(typed_placeholders("{{ :int 10..1 }}").is_err());
+        assert!(typed_placeholders("{{ :int != 1 }}").is_err());
+        assert!(typed_placeholders("{{ name }}").unwrap().is_empty());
+    }
+}
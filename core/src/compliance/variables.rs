@@ -0,0 +1,250 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    error, fmt,
+};
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::{
+    config::FlatConfigItem,
+    parse::{span::Position, ItemsContainer},
+};
+
+use super::{
+    matcher::escape_literal, options::ComplianceOptionsContainer, typed::typed_placeholders,
+};
+
+lazy_static! {
+    static ref VARIABLE_REGEX: Regex =
+        Regex::new(r"\{\{\s*(?<name>[A-Za-z_][\w.-]*)\s*\}\}").unwrap();
+}
+
+/// Values of the `{{ var }}` placeholders of a policy, by name.
+pub type Variables = BTreeMap<String, String>;
+
+#[derive(Debug)]
+pub enum VariableError {
+    /// Variable name, policy line and its position.
+    Missing(String, String, Position),
+}
+
+impl error::Error for VariableError {}
+
+impl fmt::Display for VariableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Missing(ref name, ref line, ref position) => {
+                write!(f, "Missing variable \"{name}\" {position}: \"{line}\"")
+            }
+        }
+    }
+}
+
+/// Escape unresolved placeholders of a regex item, they match their own text.
+pub(crate) fn escape_placeholders(key: &str) -> Cow<'_, str> {
+    VARIABLE_REGEX.replace_all(key, |caps: &Captures| regex::escape(&caps[0]))
+}
+
+/// Replace the placeholders of a policy item, values being escaped to match themselves.
+/// Placeholders for which `keep` is true are left as is.
+fn resolve_item_key(
+    item: &FlatConfigItem,
+    vars: &Variables,
+    keep: impl Fn(&str) -> bool,
+) -> Result<String, VariableError> {
+    let key = item.get_item_key();
+    let mut resolved = String::with_capacity(key.len());
+    let mut last = 0;
+
+    for caps in VARIABLE_REGEX.captures_iter(key) {
+        let placeholder = caps.get(0).unwrap();
+        let name = &caps["name"];
+        let value = match vars.get(name) {
+            Some(value) => value,
+            None if keep(name) => continue,
+            None => {
+                return Err(VariableError::Missing(
+                    String::from(name),
+                    String::from(key),
+                    item.get_position(),
+                ))
+            }
+        };
+
+        resolved.push_str(&key[last..placeholder.start()]);
+        resolved.push_str(&escape_literal(value, item.get_options().match_kind));
+        last = placeholder.end();
+    }
+    resolved.push_str(&key[last..]);
+
+    Ok(resolved)
+}
+
+/// Names of the regex named groups and typed placeholders of the policy, bound while
+/// checking it.
+pub(crate) fn capture_names(items: &[FlatConfigItem], names: &mut BTreeSet<String>) {
+    for item in items {
+        if let Ok(typed) = typed_placeholders(item.get_item_key()) {
+            names.extend(typed.into_iter().filter_map(|(_, typed)| typed.name));
+        }
+
+        if item.get_options().regex {
+            if let Ok(regex) = Regex::new(&escape_placeholders(item.get_item_key())) {
+                names.extend(regex.capture_names().flatten().map(String::from));
+            }
+        }
+
+        if let FlatConfigItem::Parent(parent) = item {
+            capture_names(parent.get_items(), names);
+        }
+    }
+}
+
+/// Replace the placeholders of the policy, except the ones of captured values.
+pub(crate) fn resolve_variables(
+    items: &mut [FlatConfigItem],
+    vars: &Variables,
+) -> Result<(), VariableError> {
+    let mut bound = BTreeSet::new();
+    capture_names(items, &mut bound);
+    resolve_items(items, vars, &bound)
+}
+
+fn resolve_items(
+    items: &mut [FlatConfigItem],
+    vars: &Variables,
+    bound: &BTreeSet<String>,
+) -> Result<(), VariableError> {
+    for item in items.iter_mut() {
+        let key = resolve_item_key(item, vars, |name| bound.contains(name))?;
+        item.set_item_key(key);
+
+        if let FlatConfigItem::Parent(ref mut parent) = item {
+            let mut children = parent.get_items().clone();
+            resolve_items(&mut children, vars, bound)?;
+            parent.set_items(&children);
+        }
+    }
+    Ok(())
+}
+
+/// Names of the placeholders left in a policy item and its children.
+pub(crate) fn placeholder_names(item: &FlatConfigItem, names: &mut BTreeSet<String>) {
+    names.extend(
+        VARIABLE_REGEX
+            .captures_iter(item.get_item_key())
+            .map(|caps| String::from(&caps["name"])),
+    );
+
+    if let FlatConfigItem::Parent(parent) = item {
+        for child in parent.get_items() {
+            placeholder_names(child, names);
+        }
+    }
+}
+
+/// Copy of a policy item with its placeholders replaced by the bound values, unbound
+/// placeholders are kept.
+pub(crate) fn bind_item(item: &FlatConfigItem, bindings: &Variables) -> FlatConfigItem {
+    let mut item = item.clone();
+    if let Ok(key) = resolve_item_key(&item, bindings, |_| true) {
+        item.set_item_key(key);
+    }
+    item
+}
+
+/// Values of the named groups of a regex matching a configuration key.
+pub(crate) fn captured_values(regex: &Regex, key: &str) -> Variables {
+    let Some(caps) = regex.captures(key) else {
+        return Variables::new();
+    };
+
+    regex
+        .capture_names()
+        .flatten()
+        .filter_map(|name| Some((String::from(name), String::from(caps.name(name)?.as_str()))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        compliance::{check_compliance_with_vars, ComplianceError, FlatConfigCompliance},
+        FlatConfig,
+    };
+
+    use super::*;
+
+    fn vars() -> Variables {
+        Variables::from([
+            (String::from("hostname"), String::from("R1")),
+            (String::from("ntp.primary"), String::from("10.0.0.1")),
+            (String::from("location"), String::from("Paris (DC1)")),
+        ])
+    }
+
+    #[test]
+    fn test_check_compliance_with_vars() {
+        let policy =
+            FlatConfigCompliance::from_str(include_str!("../../test/variables/1_p.txt")).unwrap();
+        let config = FlatConfig::from_str(include_str!("../../test/variables/1_c.txt")).unwrap();
+
+        let report = check_compliance_with_vars(policy, config, &vars()).unwrap();
+        assert!(report.is_compliant());
+        assert_eq!(report.summary.total(), 4);
+        assert_eq!(
+            report.results[0].result.policy.get_item_key(),
+            "hostname R1"
+        );
+    }
+
+    #[test]
+    fn test_check_compliance_with_vars_missing() {
+        let policy =
+            FlatConfigCompliance::from_str(include_str!("../../test/variables/1_p.txt")).unwrap();
+        let config = FlatConfig::from_str(include_str!("../../test/variables/1_c.txt")).unwrap();
+
+        let mut vars = vars();
+        vars.remove("location");
+        let err = check_compliance_with_vars(policy, config, &vars).unwrap_err();
+        let VariableError::Missing(ref name, ref line, position) = err;
+        assert_eq!(name, "location");
+        assert_eq!(line, "description {{location}}");
+        assert_eq!(position, Position::new(5, 2));
+    }
+
+    #[test]
+    fn test_check_compliance_with_vars_escaped() {
+        let vars = Variables::from([(String::from("site"), String::from("[abc*"))]);
+        for kind in ["regex", "glob", "prefix", "suffix", "contains"] {
+            let policy = FlatConfigCompliance::from_str(&format!(
+                "#[match-kind={kind}]\ndescription {{{{ site }}}}\n"
+            ))
+            .unwrap();
+            let config = FlatConfig::from_str("description [abc*\n").unwrap();
+
+            let report = check_compliance_with_vars(policy, config, &vars).unwrap();
+            assert!(report.is_compliant(), "{kind}");
+        }
+    }
+
+    #[test]
+    fn test_check_compliance_with_vars_invalid_pattern() {
+        // Valid when parsed, an empty class once the variable is replaced
+        let policy =
+            FlatConfigCompliance::from_str("#[regex]\ndescription [{{ site }}]\n").unwrap();
+        let config = FlatConfig::from_str("description a\n").unwrap();
+        let vars = Variables::from([(String::from("site"), String::new())]);
+
+        let report = check_compliance_with_vars(policy, config, &vars).unwrap();
+        assert!(!report.is_compliant());
+        assert!(matches!(
+            report.results[0].result.result,
+            Err(ComplianceError::InvalidPattern { ref pattern, .. }) if pattern == "description []"
+        ));
+    }
+}
@@ -3,7 +3,16 @@ use std::str::FromStr;
 use crate::{
     compliance::options::{ComplianceOptions, ComplianceOptionsContainer},
     error::FlatConfigError,
-    parse::{misc::ParseOption, parse_configuration, ItemsContainer},
+    parse::{
+        dialect::{
+            set::{into_set_hierarchy, render_set_commands},
+            Dialect,
+        },
+        misc::{nb_whitespace_at_start, ParseOption},
+        parse_configuration,
+        span::{Position, Span},
+        ItemsContainer,
+    },
 };
 
 #[derive(Debug, Default, Clone)]
@@ -12,24 +21,57 @@ pub struct FlatConfig {
     // parse_option
     // compliance_option
     pub items: Vec<FlatConfigItem>,
+    pub source: Option<String>,
 }
 
 impl FlatConfig {
     pub fn new() -> Self {
         Self::default()
     }
-}
 
-impl FromStr for FlatConfig {
-    type Err = FlatConfigError;
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = Some(String::from(source));
+        self
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    pub fn from_str_with_dialect(s: &str, dialect: Dialect) -> Result<Self, FlatConfigError> {
+        Self::from_str_with_options(
+            s,
+            ParseOption {
+                dialect,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn from_str_with_options(s: &str, options: ParseOption) -> Result<Self, FlatConfigError> {
         let options = ParseOption {
             ignore_options: true,
-            ..Default::default()
+            ..options
         };
         Ok(parse_configuration(s, Some(options))?)
     }
+
+    /// Split every item into one level per word, to compare with a `set` dialect policy.
+    pub fn into_set_hierarchy(mut self) -> Self {
+        self.items = into_set_hierarchy(self.items);
+        self
+    }
+
+    /// Render the configuration as `set` commands, whatever the dialect it was read from.
+    pub fn to_set_commands(&self) -> String {
+        let mut commands = vec![];
+        render_set_commands(&self.items, &mut vec![], &mut commands);
+        commands.iter().map(|c| format!("{c}\n")).collect()
+    }
+}
+
+impl FromStr for FlatConfig {
+    type Err = FlatConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_dialect(s, Dialect::default())
+    }
 }
 
 impl ItemsContainer for FlatConfig {
@@ -90,7 +132,7 @@ impl ComplianceOptionsContainer for FlatConfigItem {
         }
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, test))]
     fn get_raw_options(&self) -> &Vec<String> {
         match self {
             FlatConfigItem::Line(line) => line.get_raw_options(),
@@ -112,13 +154,84 @@ impl ComplianceOptionsContainer for FlatConfigItem {
 }
 
 impl FlatConfigItem {
-    pub(crate) fn get_item_key(&self) -> &str {
+    /// Index of the line in the original text, starting at 0.
+    pub fn get_index(&self) -> usize {
+        match &self {
+            FlatConfigItem::Line(line) => line.index,
+            FlatConfigItem::Parent(parent) => parent.index,
+        }
+    }
+
+    pub fn get_span(&self) -> Span {
+        match &self {
+            FlatConfigItem::Line(line) => line.span,
+            FlatConfigItem::Parent(parent) => parent.span,
+        }
+    }
+
+    /// Position of the first non blank character in the original text.
+    pub fn get_position(&self) -> Position {
+        self.get_span()
+            .position(nb_whitespace_at_start(self.get_raw()) + 1)
+    }
+
+    /// Policy file the item was included from, none for items of the file itself.
+    pub fn get_file(&self) -> Option<&str> {
+        match &self {
+            FlatConfigItem::Line(line) => line.file.as_deref(),
+            FlatConfigItem::Parent(parent) => parent.file.as_deref(),
+        }
+    }
+
+    pub(crate) fn set_file(&mut self, file: &str) {
+        match self {
+            FlatConfigItem::Line(ref mut line) => {
+                line.file = Some(String::from(file));
+            }
+            FlatConfigItem::Parent(ref mut parent) => {
+                parent.file = Some(String::from(file));
+                for item in parent.items.iter_mut() {
+                    item.set_file(file);
+                }
+            }
+        }
+    }
+
+    /// Untrimmed text of the line in the original text.
+    pub fn get_raw(&self) -> &str {
+        match &self {
+            FlatConfigItem::Line(line) => &line.raw,
+            FlatConfigItem::Parent(parent) => &parent.raw,
+        }
+    }
+
+    pub(crate) fn set_source(&mut self, span: Span, raw: &str) {
+        match self {
+            FlatConfigItem::Line(ref mut line) => {
+                line.span = span;
+                line.raw = String::from(raw);
+            }
+            FlatConfigItem::Parent(ref mut parent) => {
+                parent.span = span;
+                parent.raw = String::from(raw);
+            }
+        }
+    }
+
+    pub fn get_item_key(&self) -> &str {
         match &self {
             FlatConfigItem::Line(line) => &line.line,
             FlatConfigItem::Parent(parent) => &parent.key,
         }
     }
 
+    pub(crate) fn set_item_key(&mut self, key: String) {
+        match self {
+            FlatConfigItem::Line(ref mut line) => line.line = key,
+            FlatConfigItem::Parent(ref mut parent) => parent.key = key,
+        }
+    }
+
     pub(crate) fn is_variant_eq(&self, other: &Self) -> bool {
         (matches!(self, Self::Parent(_)) && matches!(other, Self::Parent(_))
             || matches!(self, Self::Line(_)) && matches!(other, Self::Line(_)))
@@ -130,6 +243,9 @@ impl FlatConfigItem {
 pub struct FlatConfigLine {
     pub index: usize,
     pub line: String,
+    pub span: Span,
+    pub raw: String,
+    pub file: Option<String>,
     #[cfg(debug_assertions)]
     pub raw_options: Vec<String>,
     pub options: ComplianceOptions,
@@ -137,14 +253,14 @@ pub struct FlatConfigLine {
 
 impl ComplianceOptionsContainer for FlatConfigLine {
     fn get_options(&self) -> ComplianceOptions {
-        self.options
+        self.options.clone()
     }
 
     fn set_options(&mut self, options: ComplianceOptions) {
         self.options = options;
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, test))]
     fn get_raw_options(&self) -> &Vec<String> {
         &self.raw_options
     }
@@ -168,6 +284,9 @@ impl FlatConfigLine {
         Self {
             index: p.index,
             line: p.key,
+            span: p.span,
+            raw: p.raw,
+            file: p.file,
             #[cfg(debug_assertions)]
             raw_options: p.raw_options,
             options: p.options,
@@ -187,6 +306,9 @@ pub struct FlatConfigParent {
     pub index: usize,
     pub indent: usize,
     pub key: String,
+    pub span: Span,
+    pub raw: String,
+    pub file: Option<String>,
     pub items: Vec<FlatConfigItem>,
     #[cfg(debug_assertions)]
     raw_options: Vec<String>,
@@ -217,6 +339,21 @@ impl FlatConfigParent {
             ..Default::default()
         }
     }
+
+    pub(crate) fn from_line(l: FlatConfigLine, indent: usize) -> Self {
+        Self {
+            index: l.index,
+            indent,
+            key: l.line,
+            span: l.span,
+            raw: l.raw,
+            file: l.file,
+            items: vec![],
+            #[cfg(debug_assertions)]
+            raw_options: l.raw_options,
+            options: l.options,
+        }
+    }
 }
 
 impl ItemsContainer for FlatConfigParent {
@@ -247,14 +384,14 @@ impl ItemsContainer for FlatConfigParent {
 
 impl ComplianceOptionsContainer for FlatConfigParent {
     fn get_options(&self) -> ComplianceOptions {
-        self.options
+        self.options.clone()
     }
 
     fn set_options(&mut self, options: ComplianceOptions) {
         self.options = options;
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, test))]
     fn get_raw_options(&self) -> &Vec<String> {
         &self.raw_options
     }
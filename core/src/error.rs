@@ -9,6 +9,14 @@ pub use crate::parse::error::ParseError;
 pub enum FlatConfigError {
     Parse(ParseError),
     IO(io::Error),
+    /// Error of a policy file included at a line of the including policy.
+    Include {
+        path: String,
+        line: usize,
+        error: Box<FlatConfigError>,
+    },
+    /// Files including each other, the first one being included again.
+    IncludeCycle(Vec<String>),
 }
 
 impl error::Error for FlatConfigError {}
@@ -22,6 +30,16 @@ impl fmt::Display for FlatConfigError {
             Self::IO(ref err) => {
                 write!(f, "Flat configuration IO error: {err}")
             }
+            Self::Include {
+                ref path,
+                line,
+                ref error,
+            } => {
+                write!(f, "in \"{path}\" included line {line}: {error}")
+            }
+            Self::IncludeCycle(ref chain) => {
+                write!(f, "Include cycle: {}", chain.join(" > "))
+            }
         }
     }
 }
@@ -31,9 +49,3 @@ impl From<ParseError> for FlatConfigError {
         Self::Parse(err)
     }
 }
-
-impl From<ComplianceOptionParseError> for FlatConfigError {
-    fn from(err: ComplianceOptionParseError) -> Self {
-        Self::Parse(err.into())
-    }
-}
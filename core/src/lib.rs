@@ -4,5 +4,9 @@ pub mod error;
 
 pub use compliance::FlatConfigCompliance;
 pub use config::FlatConfig;
+pub use parse::dialect::Dialect;
+pub use parse::literal::{LiteralBlock, LiteralDelimiter};
+pub use parse::misc::ParseOption;
+pub use parse::span::{Position, Span};
 
 pub(crate) mod parse;
@@ -0,0 +1,305 @@
+use log::trace;
+
+use crate::{
+    compliance::options::parse::COMPLIANCE_OPTION_REGEX,
+    config::{FlatConfigItem, FlatConfigLine, FlatConfigParent},
+    parse::{error::ParseError, span::Position, ItemsContainer},
+};
+
+const INACTIVE_MARKER: &str = "inactive:";
+
+struct OpenBlock {
+    parent: FlatConfigParent,
+    inactive: bool,
+    position: Position,
+}
+
+#[derive(Default)]
+struct BraceBuilder {
+    root: Vec<FlatConfigItem>,
+    stack: Vec<OpenBlock>,
+    statement: String,
+    statement_index: usize,
+}
+
+/// Remove the `inactive:` marker, return true if it was present.
+fn strip_inactive(key: &str) -> (&str, bool) {
+    match key.strip_prefix(INACTIVE_MARKER) {
+        Some(key) => (key.trim_start(), true),
+        None => (key, false),
+    }
+}
+
+impl BraceBuilder {
+    fn push(&mut self, item: FlatConfigItem) {
+        match self.stack.last_mut() {
+            Some(block) => block.parent.items.push(item),
+            None => self.root.push(item),
+        }
+    }
+
+    fn append(&mut self, index: usize, c: char) {
+        if self.statement.is_empty() {
+            if c.is_whitespace() {
+                return;
+            }
+            self.statement_index = index;
+        }
+        self.statement.push(c);
+    }
+
+    fn end_statement(&mut self) {
+        let statement = std::mem::take(&mut self.statement);
+        let (key, inactive) = strip_inactive(statement.trim());
+        if key.is_empty() {
+            return;
+        }
+
+        if inactive {
+            trace!("brace: skip inactive statement {}", key);
+            return;
+        }
+
+        self.push(FlatConfigItem::Line(FlatConfigLine::new(
+            self.statement_index,
+            key,
+        )));
+    }
+
+    fn open_block(&mut self, column: usize) {
+        let statement = std::mem::take(&mut self.statement);
+        let (key, inactive) = strip_inactive(statement.trim());
+
+        self.stack.push(OpenBlock {
+            parent: FlatConfigParent::new(
+                self.statement_index,
+                self.stack.len() + 1,
+                String::from(key),
+            ),
+            inactive,
+            position: Position::new(self.statement_index + 1, column),
+        });
+    }
+
+    fn close_block(&mut self, index: usize, column: usize) -> Result<(), ParseError> {
+        self.end_statement();
+
+        let Some(block) = self.stack.pop() else {
+            return Err(ParseError::UnbalancedBlock(
+                String::from("}"),
+                Position::new(index + 1, column),
+            ));
+        };
+
+        if block.inactive {
+            trace!("brace: skip inactive block {}", block.parent.key);
+            return Ok(());
+        }
+
+        if block.parent.items.is_empty() {
+            self.push(FlatConfigItem::Line(block.parent.into()));
+        } else {
+            self.push(FlatConfigItem::Parent(block.parent));
+        }
+        Ok(())
+    }
+}
+
+/// Build the hierarchy from `{ ... }` blocks and `;` terminated statements.
+///
+/// `/* */` and `#` comments are ignored, except fcc options lines. A `#` only starts a
+/// comment at the beginning of a word, values like `abc#1` keep it.
+/// Statements and blocks marked `inactive:` are dropped.
+pub(crate) fn process_brace_blocks(
+    vals: &mut impl Iterator<Item = (usize, String)>,
+    parent: &mut dyn ItemsContainer,
+) -> Result<(), ParseError> {
+    let mut builder = BraceBuilder::default();
+    let mut in_comment = false;
+    let mut in_quote = false;
+
+    for (index, line) in vals {
+        if !in_comment
+            && !in_quote
+            && builder.statement.is_empty()
+            && COMPLIANCE_OPTION_REGEX.is_match(&line)
+        {
+            builder.push(FlatConfigItem::Line(FlatConfigLine::new(
+                index,
+                line.trim(),
+            )));
+            continue;
+        }
+
+        let mut chars = line.chars().enumerate().peekable();
+        let mut prev = None;
+        while let Some((column, c)) = chars.next() {
+            let word_start = prev.is_none_or(|p: char| p.is_whitespace() || "{};".contains(p));
+            prev = Some(c);
+
+            if in_comment {
+                if c == '*' && chars.next_if(|(_, c)| *c == '/').is_some() {
+                    in_comment = false;
+                }
+                continue;
+            }
+
+            if in_quote {
+                builder.append(index, c);
+                if c == '\\' {
+                    if let Some((_, escaped)) = chars.next() {
+                        builder.append(index, escaped);
+                    }
+                } else if c == '"' {
+                    in_quote = false;
+                }
+                continue;
+            }
+
+            match c {
+                '"' => {
+                    in_quote = true;
+                    builder.append(index, c);
+                }
+                '/' if chars.next_if(|(_, c)| *c == '*').is_some() => {
+                    in_comment = true;
+                }
+                '#' if word_start => break,
+                '{' => builder.open_block(column + 1),
+                '}' => builder.close_block(index, column + 1)?,
+                ';' => builder.end_statement(),
+                c => builder.append(index, c),
+            }
+        }
+
+        if in_quote {
+            builder.append(index, '\n');
+        } else {
+            // Be lenient with statements missing their ending ';'
+            builder.end_statement();
+        }
+    }
+
+    if let Some(block) = builder.stack.pop() {
+        return Err(ParseError::UnbalancedBlock(
+            block.parent.key,
+            block.position,
+        ));
+    }
+
+    parent.appends_items(&builder.root);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        compliance::check_compliance, config::FlatConfig, parse::dialect::Dialect,
+        FlatConfigCompliance,
+    };
+
+    use super::*;
+
+    fn lines(raw: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+        raw.lines().map(String::from).enumerate()
+    }
+
+    #[test]
+    fn test_process_brace_blocks() {
+        let mut config = FlatConfig::default();
+        process_brace_blocks(
+            &mut lines(include_str!("../../../test/brace/1.txt")),
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(config.items.len(), 3);
+
+        let FlatConfigItem::Parent(system) = &config.items[0] else {
+            panic!()
+        };
+        assert_eq!(system.key, "system");
+        assert_eq!(system.index, 1);
+        assert_eq!(system.items.len(), 4);
+        assert_eq!(system.items[0].get_item_key(), "host-name R1");
+        let FlatConfigItem::Parent(login) = &system.items[1] else {
+            panic!()
+        };
+        assert_eq!(
+            login.items[0].get_item_key(),
+            "message \"do not # enter; {\""
+        );
+        assert!(matches!(system.items[2], FlatConfigItem::Parent(_)));
+        assert!(matches!(system.items[3], FlatConfigItem::Line(_)));
+
+        let FlatConfigItem::Parent(interfaces) = &config.items[1] else {
+            panic!()
+        };
+        // inactive interface is dropped
+        assert_eq!(interfaces.items.len(), 1);
+        assert_eq!(interfaces.items[0].get_item_key(), "ge-0/0/0");
+    }
+
+    #[test]
+    fn test_process_brace_blocks_hash_in_value() {
+        let mut config = FlatConfig::default();
+        process_brace_blocks(
+            &mut lines("snmp {\n community abc#1; # comment\n}# comment\n# comment\n"),
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(config.items.len(), 1);
+        let FlatConfigItem::Parent(snmp) = &config.items[0] else {
+            panic!()
+        };
+        assert_eq!(snmp.items.len(), 1);
+        assert_eq!(snmp.items[0].get_item_key(), "community abc#1");
+    }
+
+    #[test]
+    fn test_process_brace_blocks_unbalanced() {
+        let mut config = FlatConfig::default();
+        let err = process_brace_blocks(&mut lines("system {\n host-name R1;\n"), &mut config)
+            .unwrap_err();
+        assert!(matches!(err, ParseError::UnbalancedBlock(_, _)));
+        assert_eq!(err.position(), Position::new(1, 8));
+
+        let err = process_brace_blocks(&mut lines("host-name R1;\n}"), &mut config).unwrap_err();
+        assert!(matches!(err, ParseError::UnbalancedBlock(_, _)));
+        assert_eq!(err.position(), Position::new(2, 1));
+    }
+
+    #[test]
+    fn test_brace_indent_policy() {
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/brace/1.txt"),
+            Dialect::Brace,
+        )
+        .unwrap();
+        let policy =
+            FlatConfigCompliance::from_str(include_str!("../../../test/brace/1_p.txt")).unwrap();
+
+        let report = check_compliance(policy, config);
+        assert!(report.is_compliant());
+        assert_eq!(report.summary.total(), 12);
+
+        let policy = FlatConfigCompliance::from_str_with_dialect(
+            include_str!("../../../test/brace/1_p_brace.txt"),
+            Dialect::Brace,
+        )
+        .unwrap();
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/brace/1.txt"),
+            Dialect::Brace,
+        )
+        .unwrap();
+
+        let report = check_compliance(policy, config);
+        assert!(report.is_compliant());
+        assert_eq!(report.summary.total(), 12);
+    }
+}
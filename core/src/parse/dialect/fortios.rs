@@ -0,0 +1,241 @@
+use log::trace;
+
+use crate::{
+    compliance::options::parse::COMPLIANCE_OPTION_REGEX,
+    config::{FlatConfigItem, FlatConfigLine, FlatConfigParent},
+    parse::{error::ParseError, span::Position, ItemsContainer},
+};
+
+const CONFIG_KEYWORD: &str = "config";
+const EDIT_KEYWORD: &str = "edit";
+const NEXT_KEYWORD: &str = "next";
+const END_KEYWORD: &str = "end";
+
+struct OpenBlock {
+    parent: FlatConfigParent,
+    position: Position,
+}
+
+#[derive(Default)]
+struct FortiosBuilder {
+    root: Vec<FlatConfigItem>,
+    stack: Vec<OpenBlock>,
+}
+
+fn first_word(statement: &str) -> &str {
+    statement.split_whitespace().next().unwrap_or_default()
+}
+
+/// True if the statement leaves a quoted string open.
+fn has_open_quote(statement: &str) -> bool {
+    let mut in_quote = false;
+    let mut escaped = false;
+    for c in statement.chars() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            in_quote = !in_quote;
+        }
+    }
+    in_quote
+}
+
+impl FortiosBuilder {
+    fn push(&mut self, item: FlatConfigItem) {
+        match self.stack.last_mut() {
+            Some(block) => block.parent.items.push(item),
+            None => self.root.push(item),
+        }
+    }
+
+    fn open_block(&mut self, index: usize, column: usize, key: &str) {
+        self.stack.push(OpenBlock {
+            parent: FlatConfigParent::new(index, self.stack.len() + 1, String::from(key)),
+            position: Position::new(index + 1, column),
+        });
+    }
+
+    /// Close the last block, which must have been opened by `opening`.
+    fn close_block(
+        &mut self,
+        index: usize,
+        column: usize,
+        keyword: &str,
+        opening: &str,
+    ) -> Result<(), ParseError> {
+        let block = match self.stack.pop() {
+            Some(block) if first_word(&block.parent.key) == opening => block,
+            _ => {
+                return Err(ParseError::UnbalancedBlock(
+                    String::from(keyword),
+                    Position::new(index + 1, column),
+                ))
+            }
+        };
+
+        if block.parent.items.is_empty() {
+            self.push(FlatConfigItem::Line(block.parent.into()));
+        } else {
+            self.push(FlatConfigItem::Parent(block.parent));
+        }
+        Ok(())
+    }
+}
+
+/// Build the hierarchy from `config`/`end` and `edit`/`next` blocks, indentation is ignored.
+///
+/// Terminator lines are dropped, `#` comments are ignored except fcc options lines.
+/// A quoted value spanning several lines is kept as a single statement.
+pub(crate) fn process_fortios_blocks(
+    vals: &mut impl Iterator<Item = (usize, String)>,
+    parent: &mut dyn ItemsContainer,
+) -> Result<(), ParseError> {
+    let mut builder = FortiosBuilder::default();
+    let mut statement: Option<(usize, String)> = None;
+
+    for (index, line) in vals {
+        if let Some((start, mut value)) = statement.take() {
+            value.push('\n');
+            value.push_str(&line);
+            if has_open_quote(&value) {
+                statement = Some((start, value));
+            } else {
+                builder.push(FlatConfigItem::Line(FlatConfigLine::new(start, &value)));
+            }
+            continue;
+        }
+
+        if COMPLIANCE_OPTION_REGEX.is_match(&line) {
+            builder.push(FlatConfigItem::Line(FlatConfigLine::new(
+                index,
+                line.trim(),
+            )));
+            continue;
+        }
+
+        let key = line.trim();
+        let column = line.len() - line.trim_start().len() + 1;
+        match first_word(key) {
+            "" => {}
+            c if c.starts_with('#') => trace!("fortios: skip comment {}", key),
+            CONFIG_KEYWORD | EDIT_KEYWORD => builder.open_block(index, column, key),
+            NEXT_KEYWORD => builder.close_block(index, column, NEXT_KEYWORD, EDIT_KEYWORD)?,
+            END_KEYWORD => builder.close_block(index, column, END_KEYWORD, CONFIG_KEYWORD)?,
+            _ if has_open_quote(key) => statement = Some((index, String::from(key))),
+            _ => builder.push(FlatConfigItem::Line(FlatConfigLine::new(index, key))),
+        }
+    }
+
+    if let Some((start, value)) = statement {
+        return Err(ParseError::UnbalancedBlock(
+            value,
+            Position::new(start + 1, 1),
+        ));
+    }
+
+    if let Some(block) = builder.stack.pop() {
+        return Err(ParseError::UnbalancedBlock(
+            block.parent.key,
+            block.position,
+        ));
+    }
+
+    parent.appends_items(&builder.root);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compliance::check_compliance, config::FlatConfig, parse::dialect::Dialect,
+        FlatConfigCompliance,
+    };
+
+    use super::*;
+
+    fn lines(raw: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+        raw.lines().map(String::from).enumerate()
+    }
+
+    #[test]
+    fn test_process_fortios_blocks() {
+        let mut config = FlatConfig::default();
+        process_fortios_blocks(
+            &mut lines(include_str!("../../../test/fortios/1.txt")),
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(config.items.len(), 4);
+
+        let FlatConfigItem::Parent(global) = &config.items[0] else {
+            panic!()
+        };
+        assert_eq!(global.key, "config system global");
+        assert_eq!(global.items.len(), 3);
+        assert_eq!(global.items[0].get_item_key(), "set hostname \"FW01\"");
+
+        let FlatConfigItem::Parent(interface) = &config.items[1] else {
+            panic!()
+        };
+        assert_eq!(interface.items.len(), 2);
+        let FlatConfigItem::Parent(port1) = &interface.items[0] else {
+            panic!()
+        };
+        assert_eq!(port1.key, "edit \"port1\"");
+        assert_eq!(port1.items.len(), 3);
+        assert_eq!(port1.items[2].get_item_key(), "unset description");
+
+        // empty block
+        assert!(matches!(config.items[2], FlatConfigItem::Line(_)));
+
+        let FlatConfigItem::Parent(banner) = &config.items[3] else {
+            panic!()
+        };
+        assert_eq!(
+            banner.items[0].get_item_key(),
+            "set buffer \"line one\nline two\""
+        );
+    }
+
+    #[test]
+    fn test_process_fortios_blocks_unbalanced() {
+        let mut config = FlatConfig::default();
+        let err = process_fortios_blocks(
+            &mut lines("config system global\n    set hostname FW01\n"),
+            &mut config,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ParseError::UnbalancedBlock(_, _)));
+        assert_eq!(err.position(), Position::new(1, 1));
+
+        let err = process_fortios_blocks(
+            &mut lines("config system interface\n    edit \"port1\"\n    end\n"),
+            &mut config,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ParseError::UnbalancedBlock(_, _)));
+        assert_eq!(err.position(), Position::new(3, 5));
+    }
+
+    #[test]
+    fn test_fortios_policy() {
+        let policy = FlatConfigCompliance::from_str_with_dialect(
+            include_str!("../../../test/fortios/1_p.txt"),
+            Dialect::Fortios,
+        )
+        .unwrap();
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/fortios/1.txt"),
+            Dialect::Fortios,
+        )
+        .unwrap();
+
+        let report = check_compliance(policy, config);
+        assert_eq!(report.summary.failed, 1);
+        assert_eq!(report.summary.total(), 8);
+    }
+}
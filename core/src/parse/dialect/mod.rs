@@ -0,0 +1,36 @@
+pub(crate) mod brace;
+pub(crate) mod fortios;
+pub(crate) mod set;
+pub(crate) mod vrp;
+
+use strum::{Display, EnumString, VariantNames};
+
+use super::literal::LiteralBlock;
+
+/// Syntax used to express the configuration hierarchy.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, Display, EnumString, VariantNames, PartialEq, Eq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Dialect {
+    /// Hierarchy given by indentation (Cisco IOS like)
+    #[default]
+    Indent,
+    /// `{ ... }` blocks and `;` terminated statements (Junos, PAN-OS like)
+    Brace,
+    /// One `set` statement per line, every word being a level (Junos `display set`)
+    Set,
+    /// `config`/`end` and `edit`/`next` blocks (FortiOS)
+    Fortios,
+    /// Indentation with `#` separators and `quit`/`return` closing views (Huawei VRP, H3C Comware)
+    Vrp,
+}
+
+impl Dialect {
+    /// Literal blocks recognized when none are given.
+    pub fn literal_blocks(&self) -> Vec<LiteralBlock> {
+        match self {
+            Self::Indent => LiteralBlock::ios_defaults(),
+            _ => vec![],
+        }
+    }
+}
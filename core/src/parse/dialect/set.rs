@@ -0,0 +1,377 @@
+use log::trace;
+
+use crate::{
+    compliance::{
+        options::{parse::COMPLIANCE_OPTION_REGEX, ComplianceOptionsContainer, StateOption},
+        typed::has_typed_placeholders,
+    },
+    config::{FlatConfigItem, FlatConfigLine, FlatConfigParent},
+    parse::{error::ParseError, ItemsContainer},
+};
+
+const SET_COMMAND: &str = "set";
+const DEACTIVATE_COMMAND: &str = "deactivate";
+
+/// Split a statement into words, quoted strings are kept whole.
+pub(crate) fn split_words(statement: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = None;
+    let mut in_quote = false;
+    let mut escaped = false;
+
+    for (i, c) in statement.char_indices() {
+        if in_quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_quote = false;
+            }
+            continue;
+        }
+
+        if c.is_whitespace() {
+            if let Some(s) = start.take() {
+                words.push(&statement[s..i]);
+            }
+            continue;
+        }
+
+        if start.is_none() {
+            start = Some(i);
+        }
+        if c == '"' {
+            in_quote = true;
+        }
+    }
+
+    if let Some(s) = start {
+        words.push(&statement[s..]);
+    }
+    words
+}
+
+fn find(items: &[FlatConfigItem], key: &str) -> Option<usize> {
+    items.iter().position(|i| i.get_item_key() == key)
+}
+
+/// Get the `key` block, turning an existing line into a block if needed.
+/// Return true if the block was created.
+fn enter<'a>(
+    items: &'a mut Vec<FlatConfigItem>,
+    key: &str,
+    index: usize,
+    depth: usize,
+) -> (&'a mut FlatConfigParent, bool) {
+    let (i, created) = match find(items, key) {
+        Some(i) => {
+            if let FlatConfigItem::Line(line) = &items[i] {
+                items[i] =
+                    FlatConfigItem::Parent(FlatConfigParent::from_line(line.clone(), depth + 1));
+            }
+            (i, false)
+        }
+        None => {
+            items.push(FlatConfigItem::Parent(FlatConfigParent::new(
+                index,
+                depth + 1,
+                String::from(key),
+            )));
+            (items.len() - 1, true)
+        }
+    };
+
+    let FlatConfigItem::Parent(parent) = &mut items[i] else {
+        unreachable!()
+    };
+    (parent, created)
+}
+
+/// Add a `set` statement path, fcc options lines are put just before the last word.
+fn insert_path(
+    root: &mut Vec<FlatConfigItem>,
+    path: &[&str],
+    index: usize,
+    options: Vec<FlatConfigItem>,
+) {
+    let Some((leaf, levels)) = path.split_last() else {
+        return;
+    };
+
+    let mut items = root;
+    for (depth, key) in levels.iter().enumerate() {
+        items = &mut enter(items, key, index, depth).0.items;
+    }
+
+    let i = find(items, leaf).unwrap_or_else(|| {
+        items.push(FlatConfigItem::Line(FlatConfigLine::new(index, leaf)));
+        items.len() - 1
+    });
+    items.splice(i..i, options);
+}
+
+fn remove_path(root: &mut Vec<FlatConfigItem>, path: &[&str]) {
+    let Some((leaf, levels)) = path.split_last() else {
+        return;
+    };
+
+    let mut items = root;
+    for key in levels {
+        match find(items, key).map(|i| &mut items[i]) {
+            Some(FlatConfigItem::Parent(parent)) => items = &mut parent.items,
+            _ => return,
+        }
+    }
+    items.retain(|i| i.get_item_key() != *leaf);
+}
+
+/// Build the hierarchy from `set` commands, every word of a statement is a level.
+///
+/// Statements removed by a `deactivate` command are dropped, any other command is ignored.
+/// fcc options apply to the last word of the following statement.
+pub(crate) fn process_set_commands(
+    vals: &mut impl Iterator<Item = (usize, String)>,
+    parent: &mut dyn ItemsContainer,
+) -> Result<(), ParseError> {
+    let mut root = vec![];
+    let mut options = vec![];
+    let mut deactivated: Vec<Vec<String>> = vec![];
+
+    for (index, line) in vals {
+        if COMPLIANCE_OPTION_REGEX.is_match(&line) {
+            options.push(FlatConfigItem::Line(FlatConfigLine::new(
+                index,
+                line.trim(),
+            )));
+            continue;
+        }
+
+        let words = split_words(&line);
+        match words.split_first() {
+            Some((&SET_COMMAND, path)) => {
+                insert_path(&mut root, path, index, std::mem::take(&mut options));
+            }
+            Some((&DEACTIVATE_COMMAND, path)) => {
+                deactivated.push(path.iter().map(|w| String::from(*w)).collect());
+            }
+            _ => trace!("set: skip statement {}", line.trim()),
+        }
+    }
+
+    for path in deactivated {
+        trace!("set: skip inactive statement {}", path.join(" "));
+        remove_path(
+            &mut root,
+            &path.iter().map(String::as_str).collect::<Vec<_>>(),
+        );
+    }
+
+    // Options without statement are left for process_fcc_options to report
+    root.append(&mut options);
+    parent.appends_items(&root);
+
+    Ok(())
+}
+
+fn insert_item(items: &mut Vec<FlatConfigItem>, item: FlatConfigItem, depth: usize) {
+    let options = item.get_options();
+    let key = String::from(item.get_item_key());
+    // Patterns and placeholders can contain spaces
+    let words = if options.match_kind.is_pattern() || has_typed_placeholders(&key) {
+        vec![key.as_str()]
+    } else {
+        split_words(&key)
+    };
+    let Some((leaf, levels)) = words.split_last() else {
+        return;
+    };
+
+    // Levels added by the split are only required if the item is
+    let level_state = match options.state {
+        StateOption::Present => StateOption::Present,
+        _ => StateOption::Optional,
+    };
+
+    let mut items = items;
+    for (i, word) in levels.iter().enumerate() {
+        let (parent, created) = enter(items, word, item.get_index(), depth + i);
+        if created {
+            parent.span = item.get_span();
+            parent.raw = String::from(item.get_raw());
+            parent.file = item.get_file().map(String::from);
+            parent.options.state = level_state;
+        } else if matches!(level_state, StateOption::Present) {
+            parent.options.state = level_state;
+        }
+        items = &mut parent.items;
+    }
+    let depth = depth + levels.len();
+
+    match item {
+        FlatConfigItem::Line(mut line) => match find(items, leaf) {
+            Some(i) => items[i].set_options(options),
+            None => {
+                line.line = String::from(*leaf);
+                items.push(FlatConfigItem::Line(line));
+            }
+        },
+        FlatConfigItem::Parent(mut parent) => {
+            let children = std::mem::take(&mut parent.items);
+            let (block, created) = enter(items, leaf, parent.index, depth);
+            if created {
+                parent.key = String::from(*leaf);
+                parent.indent = depth + 1;
+                *block = parent;
+            } else {
+                block.options = options;
+            }
+
+            for child in children {
+                insert_item(&mut block.items, child, depth + 1);
+            }
+        }
+    }
+}
+
+/// Restructure a hierarchy so every word is a level, like the `set` dialect builds it.
+///
+/// Regex items are kept whole. Options apply to the last word.
+pub(crate) fn into_set_hierarchy(items: Vec<FlatConfigItem>) -> Vec<FlatConfigItem> {
+    let mut root = vec![];
+    for item in items {
+        insert_item(&mut root, item, 0);
+    }
+    root
+}
+
+/// Render a hierarchy as `set` commands, one per line without children.
+pub(crate) fn render_set_commands<'a>(
+    items: &'a [FlatConfigItem],
+    path: &mut Vec<&'a str>,
+    commands: &mut Vec<String>,
+) {
+    for item in items {
+        path.push(item.get_item_key());
+        match item {
+            FlatConfigItem::Parent(parent) if !parent.items.is_empty() => {
+                render_set_commands(&parent.items, path, commands);
+            }
+            _ => commands.push(format!("{SET_COMMAND} {}", path.join(" "))),
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        compliance::check_compliance, config::FlatConfig, parse::dialect::Dialect,
+        FlatConfigCompliance,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words("set system  host-name R1"),
+            vec!["set", "system", "host-name", "R1"]
+        );
+        assert_eq!(
+            split_words("message \"do not \\\" enter\" now"),
+            vec!["message", "\"do not \\\" enter\"", "now"]
+        );
+        assert!(split_words("   ").is_empty());
+    }
+
+    #[test]
+    fn test_process_set_commands() {
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/set/1.txt"),
+            Dialect::Set,
+        )
+        .unwrap();
+
+        assert_eq!(config.items.len(), 3);
+        let FlatConfigItem::Parent(system) = &config.items[0] else {
+            panic!()
+        };
+        assert_eq!(system.key, "system");
+        assert_eq!(system.index, 1);
+        assert_eq!(system.items.len(), 4);
+
+        let FlatConfigItem::Parent(interfaces) = &config.items[1] else {
+            panic!()
+        };
+        // deactivated interface is dropped
+        assert_eq!(interfaces.items.len(), 1);
+        assert_eq!(interfaces.items[0].get_item_key(), "ge-0/0/0");
+
+        assert_eq!(
+            config.to_set_commands(),
+            include_str!("../../../test/set/1_r.txt")
+        );
+    }
+
+    #[test]
+    fn test_into_set_hierarchy() {
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/brace/1.txt"),
+            Dialect::Brace,
+        )
+        .unwrap();
+        // Rendering is the same with or without restructuring
+        assert_eq!(
+            config.to_set_commands(),
+            include_str!("../../../test/set/1_r.txt")
+        );
+        assert_eq!(
+            config.into_set_hierarchy().to_set_commands(),
+            include_str!("../../../test/set/1_r.txt")
+        );
+    }
+
+    #[test]
+    fn test_set_policy() {
+        // Hierarchical policy against set commands
+        let policy = FlatConfigCompliance::from_str(include_str!("../../../test/brace/1_p.txt"))
+            .unwrap()
+            .into_set_hierarchy();
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/set/1.txt"),
+            Dialect::Set,
+        )
+        .unwrap();
+
+        let report = check_compliance(policy, config);
+        assert!(report.is_compliant());
+
+        // Set policy against hierarchical configuration
+        let policy = FlatConfigCompliance::from_str_with_dialect(
+            include_str!("../../../test/set/1_p.txt"),
+            Dialect::Set,
+        )
+        .unwrap();
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/brace/1.txt"),
+            Dialect::Brace,
+        )
+        .unwrap()
+        .into_set_hierarchy();
+
+        let report = check_compliance(policy, config.clone());
+        assert!(report.is_compliant());
+        assert_eq!(report.summary.failed, 0);
+
+        let policy = FlatConfigCompliance::from_str_with_dialect(
+            "#[state=absent]\nset system services ssh",
+            Dialect::Set,
+        )
+        .unwrap();
+        let report = check_compliance(policy, config);
+        assert_eq!(report.summary.failed, 1);
+    }
+}
@@ -0,0 +1,173 @@
+use log::trace;
+
+use crate::{
+    compliance::options::parse::COMPLIANCE_OPTION_REGEX,
+    config::{FlatConfigItem, FlatConfigLine, FlatConfigParent},
+    parse::{error::ParseError, misc::nb_whitespace_at_start, ItemsContainer},
+};
+
+const SEPARATOR: &str = "#";
+const QUIT_COMMAND: &str = "quit";
+const RETURN_COMMAND: &str = "return";
+
+struct OpenView {
+    parent: FlatConfigParent,
+    indent: usize,
+}
+
+#[derive(Default)]
+struct VrpBuilder {
+    root: Vec<FlatConfigItem>,
+    stack: Vec<OpenView>,
+}
+
+impl VrpBuilder {
+    fn push(&mut self, item: FlatConfigItem) {
+        match self.stack.last_mut() {
+            Some(view) => view.parent.items.push(item),
+            None => self.root.push(item),
+        }
+    }
+
+    fn open_view(&mut self, index: usize, indent: usize, key: &str) {
+        self.stack.push(OpenView {
+            parent: FlatConfigParent::new(index, self.stack.len() + 1, String::from(key)),
+            indent,
+        });
+    }
+
+    /// Close the last view, a view without children is a line.
+    fn close_view(&mut self) {
+        if let Some(view) = self.stack.pop() {
+            if view.parent.items.is_empty() {
+                self.push(FlatConfigItem::Line(view.parent.into()));
+            } else {
+                self.push(FlatConfigItem::Parent(view.parent));
+            }
+        }
+    }
+
+    /// Close the views a line with this indentation can't belong to.
+    fn close_views_from(&mut self, indent: usize) {
+        while self.stack.last().is_some_and(|v| v.indent >= indent) {
+            self.close_view();
+        }
+    }
+}
+
+/// Build the hierarchy of Huawei VRP and H3C Comware configurations.
+///
+/// Indentation gives the hierarchy, `#` separator lines close the views at their indentation
+/// or deeper, `quit` closes the current view and `return` closes all of them.
+/// Separators and view commands are dropped.
+pub(crate) fn process_vrp_views(
+    vals: &mut impl Iterator<Item = (usize, String)>,
+    parent: &mut dyn ItemsContainer,
+) -> Result<(), ParseError> {
+    let mut builder = VrpBuilder::default();
+
+    for (index, line) in vals {
+        let indent = nb_whitespace_at_start(&line);
+        let key = line.trim();
+
+        // Must be checked first, "#" is not a comment
+        if COMPLIANCE_OPTION_REGEX.is_match(&line) {
+            builder.close_views_from(indent);
+            builder.push(FlatConfigItem::Line(FlatConfigLine::new(index, key)));
+            continue;
+        }
+
+        match key {
+            SEPARATOR => builder.close_views_from(indent),
+            QUIT_COMMAND => {
+                builder.close_views_from(indent);
+                builder.close_view();
+            }
+            RETURN_COMMAND => builder.close_views_from(0),
+            _ => {
+                builder.close_views_from(indent);
+                builder.open_view(index, indent, key);
+                continue;
+            }
+        }
+        trace!("vrp: drop {}", key);
+    }
+
+    builder.close_views_from(0);
+    parent.appends_items(&builder.root);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compliance::check_compliance, config::FlatConfig, parse::dialect::Dialect,
+        FlatConfigCompliance,
+    };
+
+    use super::*;
+
+    fn lines(raw: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+        raw.lines().map(String::from).enumerate()
+    }
+
+    #[test]
+    fn test_process_vrp_views() {
+        let mut config = FlatConfig::default();
+        process_vrp_views(
+            &mut lines(include_str!("../../../test/vrp/1.txt")),
+            &mut config,
+        )
+        .unwrap();
+
+        let keys: Vec<&str> = config.items.iter().map(|i| i.get_item_key()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "sysname R1",
+                "interface GigabitEthernet0/0/1",
+                "interface GigabitEthernet0/0/2",
+                "bgp 65000",
+                "user-interface vty 0 4",
+                "ntp-service unicast-server 10.0.0.100",
+            ]
+        );
+
+        assert!(matches!(config.items[0], FlatConfigItem::Line(_)));
+
+        // closed by quit
+        let FlatConfigItem::Parent(interface) = &config.items[2] else {
+            panic!()
+        };
+        assert_eq!(interface.items.len(), 1);
+
+        let FlatConfigItem::Parent(bgp) = &config.items[3] else {
+            panic!()
+        };
+        assert_eq!(bgp.items.len(), 2);
+        let FlatConfigItem::Parent(family) = &bgp.items[1] else {
+            panic!()
+        };
+        assert_eq!(family.key, "ipv4-family unicast");
+        assert_eq!(family.items.len(), 2);
+    }
+
+    #[test]
+    fn test_vrp_policy() {
+        let policy = FlatConfigCompliance::from_str_with_dialect(
+            include_str!("../../../test/vrp/1_p.txt"),
+            Dialect::Vrp,
+        )
+        .unwrap();
+        let config = FlatConfig::from_str_with_dialect(
+            include_str!("../../../test/vrp/1.txt"),
+            Dialect::Vrp,
+        )
+        .unwrap();
+
+        let report = check_compliance(policy, config);
+        assert!(report.is_compliant());
+        assert_eq!(report.summary.total(), 8);
+    }
+}
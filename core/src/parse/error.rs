@@ -3,10 +3,23 @@ use std::fmt;
 
 use crate::compliance::options::error::ParseError as ComplianceOptionParseError;
 
+use super::span::Position;
+
 #[derive(Debug)]
 pub enum ParseError {
-    BadIndentation(String),
-    ComplianceOption(ComplianceOptionParseError),
+    BadIndentation(String, Position),
+    UnbalancedBlock(String, Position),
+    ComplianceOption(ComplianceOptionParseError, Position),
+}
+
+impl ParseError {
+    pub fn position(&self) -> Position {
+        match *self {
+            Self::BadIndentation(_, position) => position,
+            Self::UnbalancedBlock(_, position) => position,
+            Self::ComplianceOption(_, position) => position,
+        }
+    }
 }
 
 impl error::Error for ParseError {}
@@ -14,18 +27,15 @@ impl error::Error for ParseError {}
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Self::BadIndentation(ref line) => {
-                write!(f, "Indentation incoherence line: \"{line}\"")
+            Self::BadIndentation(ref line, ref position) => {
+                write!(f, "Indentation incoherence {position}: \"{line}\"")
+            }
+            Self::UnbalancedBlock(ref token, ref position) => {
+                write!(f, "Unbalanced block {position}: \"{token}\"")
             }
-            Self::ComplianceOption(ref err) => {
-                write!(f, "{err}")
+            Self::ComplianceOption(ref err, ref position) => {
+                write!(f, "{position}: {err}")
             }
         }
     }
 }
-
-impl From<ComplianceOptionParseError> for ParseError {
-    fn from(err: ComplianceOptionParseError) -> Self {
-        Self::ComplianceOption(err)
-    }
-}
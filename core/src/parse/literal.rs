@@ -0,0 +1,180 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{error::ParseError, misc::nb_whitespace_at_start, span::Position};
+
+lazy_static! {
+    static ref IOS_LITERAL_BLOCKS: Vec<LiteralBlock> = vec![
+        LiteralBlock::new(
+            Regex::new(r"^\s*banner\s+\S+\s+(?<delimiter>\^C|\S)").unwrap(),
+            LiteralDelimiter::Captured,
+        ),
+        LiteralBlock::new(
+            Regex::new(r"^\s+certificate\s+\S+").unwrap(),
+            LiteralDelimiter::Line(String::from("quit")),
+        ),
+        LiteralBlock::new(
+            Regex::new(r"^\s*macro\s+name\s+\S+").unwrap(),
+            LiteralDelimiter::Line(String::from("@")),
+        ),
+    ];
+}
+
+/// How a literal block ends.
+#[derive(Debug, Clone)]
+pub enum LiteralDelimiter {
+    /// Next occurrence of the `delimiter` group captured by the start pattern (banner).
+    Captured,
+    /// A line made of this text only (certificate `quit`, macro `@`).
+    Line(String),
+}
+
+/// Lines kept together as a single item, whatever their content and indentation.
+#[derive(Debug, Clone)]
+pub struct LiteralBlock {
+    pub start: Regex,
+    pub delimiter: LiteralDelimiter,
+}
+
+impl LiteralBlock {
+    pub fn new(start: Regex, delimiter: LiteralDelimiter) -> Self {
+        Self { start, delimiter }
+    }
+
+    /// Cisco IOS banners, certificates of a certificate chain and macros.
+    pub fn ios_defaults() -> Vec<Self> {
+        IOS_LITERAL_BLOCKS.clone()
+    }
+
+    /// Text ending the block which starts with this line, None if the line is not a start
+    /// or the block already ends on it.
+    fn end_of(&self, line: &str) -> Option<EndOfBlock> {
+        let captures = self.start.captures(line)?;
+        match self.delimiter {
+            LiteralDelimiter::Captured => {
+                let delimiter = captures.name("delimiter")?;
+                let delimiter_str = delimiter.as_str();
+                if line[delimiter.end()..].contains(delimiter_str) {
+                    return None;
+                }
+                Some(EndOfBlock::Contains(String::from(delimiter_str)))
+            }
+            LiteralDelimiter::Line(ref delimiter) => Some(EndOfBlock::Line(delimiter.clone())),
+        }
+    }
+}
+
+enum EndOfBlock {
+    Contains(String),
+    Line(String),
+}
+
+impl EndOfBlock {
+    fn is_end(&self, line: &str) -> bool {
+        match self {
+            Self::Contains(delimiter) => line.contains(delimiter.as_str()),
+            Self::Line(delimiter) => line.trim() == delimiter,
+        }
+    }
+}
+
+/// Join the lines of every literal block into a single line, at the index of its first line.
+///
+/// Must run before filtering, empty lines are part of the block.
+pub(crate) fn group_literal_blocks(
+    lines: impl Iterator<Item = (usize, String)>,
+    blocks: &[LiteralBlock],
+) -> Result<Vec<(usize, String)>, ParseError> {
+    let mut grouped = vec![];
+    let mut open: Option<(usize, String, EndOfBlock)> = None;
+
+    for (index, line) in lines {
+        if let Some((start, mut content, end)) = open.take() {
+            content.push('\n');
+            content.push_str(&line);
+            if end.is_end(&line) {
+                grouped.push((start, content));
+            } else {
+                open = Some((start, content, end));
+            }
+            continue;
+        }
+
+        match blocks.iter().find_map(|b| b.end_of(&line)) {
+            Some(end) => open = Some((index, line, end)),
+            None => grouped.push((index, line)),
+        }
+    }
+
+    if let Some((start, content, _)) = open {
+        let first_line = content.lines().next().unwrap_or_default();
+        return Err(ParseError::UnbalancedBlock(
+            String::from(first_line.trim()),
+            Position::new(start + 1, nb_whitespace_at_start(first_line) + 1),
+        ));
+    }
+
+    Ok(grouped)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{compliance::check_compliance, FlatConfig, FlatConfigCompliance};
+
+    use super::*;
+
+    fn lines(raw: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+        raw.lines().map(String::from).enumerate()
+    }
+
+    #[test]
+    fn test_group_literal_blocks() {
+        let grouped = group_literal_blocks(
+            lines(include_str!("../../test/literal/1.txt")),
+            &LiteralBlock::ios_defaults(),
+        )
+        .unwrap();
+
+        let keys: Vec<&str> = grouped.iter().map(|(_, l)| l.as_str()).collect();
+        assert_eq!(keys.len(), 11);
+        assert_eq!(keys[1], "banner exec ^Cone line^C");
+        assert_eq!(grouped[2].0, 2);
+        assert_eq!(
+            keys[2],
+            "banner motd ^C\n  Authorized access only\n\n!  #[not an option]\n^C"
+        );
+        assert_eq!(grouped[4].0, 8);
+        assert!(keys[4].ends_with("\n  \tquit"));
+        assert_eq!(
+            grouped[5],
+            (12, String::from("macro name UPLINK\nswitchport\n@"))
+        );
+    }
+
+    #[test]
+    fn test_group_literal_blocks_unterminated() {
+        let err = group_literal_blocks(
+            lines("hostname R1\n banner login #\nhello"),
+            &LiteralBlock::ios_defaults(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ParseError::UnbalancedBlock(_, _)));
+        assert_eq!(err.position(), Position::new(2, 2));
+    }
+
+    #[test]
+    fn test_literal_policy() {
+        let config = FlatConfig::from_str(include_str!("../../test/literal/1.txt")).unwrap();
+        assert_eq!(config.items.len(), 7);
+        assert_eq!(config.items[2].get_span().line, 3);
+        assert!(config.items[2].get_raw().ends_with("\n^C"));
+
+        let policy =
+            FlatConfigCompliance::from_str(include_str!("../../test/literal/1_p.txt")).unwrap();
+        let report = check_compliance(policy, config);
+        assert!(report.is_compliant());
+        assert_eq!(report.summary.total(), 4);
+    }
+}
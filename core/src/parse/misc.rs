@@ -1,13 +1,38 @@
 use regex::Regex;
 
+use super::{dialect::Dialect, literal::LiteralBlock};
+
 pub fn nb_whitespace_at_start(line: &str) -> usize {
     line.chars().take_while(|f| f.is_ascii_whitespace()).count()
 }
 
+/// Collapse the spaces and tabs between words and remove the trailing ones, the
+/// indentation of every line is kept.
+pub(crate) fn normalize_whitespace(s: &str) -> String {
+    s.split('\n')
+        .map(|line| {
+            let indent = nb_whitespace_at_start(line);
+            let words: Vec<&str> = line[indent..]
+                .split([' ', '\t'])
+                .filter(|w| !w.is_empty())
+                .collect();
+            format!("{}{}", &line[..indent], words.join(" "))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 #[derive(Debug, Default, Clone)]
-pub(crate) struct ParseOption {
+pub struct ParseOption {
+    /// Set by the configuration and policy constructors.
     pub ignore_options: bool,
+    /// Lines matching are ignored.
     pub regex_filter: Option<Regex>,
+    pub dialect: Dialect,
+    /// Literal blocks to keep as a single item, defaults of the dialect if not set.
+    pub literal_blocks: Option<Vec<LiteralBlock>>,
+    /// Collapse the whitespaces between words of every line.
+    pub normalize_whitespace: bool,
 }
 
 #[cfg(test)]
@@ -22,4 +47,16 @@ mod tests {
         assert_eq!(nb_whitespace_at_start("  line line line"), 2); // space
         assert_eq!(nb_whitespace_at_start("		line line line"), 2); // tab
     }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        assert_eq!(
+            normalize_whitespace("  ip  address\t10.0.0.1 "),
+            "  ip address 10.0.0.1"
+        );
+        assert_eq!(
+            normalize_whitespace("banner ^C\n  a   b\n^C"),
+            "banner ^C\n  a b\n^C"
+        );
+    }
 }
@@ -1,12 +1,21 @@
+pub mod dialect;
 pub mod error;
 pub mod filter;
+pub mod literal;
 pub(crate) mod misc;
+pub mod span;
 use std::{cmp::Ordering, iter::Peekable};
 
+use dialect::{
+    brace::process_brace_blocks, fortios::process_fortios_blocks, set::process_set_commands,
+    vrp::process_vrp_views, Dialect,
+};
 use error::ParseError;
 use filter::filter_line;
+use literal::group_literal_blocks;
 use log::trace;
-use misc::{nb_whitespace_at_start, ParseOption};
+use misc::{nb_whitespace_at_start, normalize_whitespace, ParseOption};
+use span::{line_spans, Position, Span};
 
 use crate::{
     compliance::options::parse::process_fcc_options,
@@ -32,22 +41,38 @@ where
 {
     let options = options.unwrap_or_default();
     let mut parent = F::default();
-    let lines = raw_config
-        .lines()
-        .map(String::from)
-        .filter(|l| filter_line(l, Some(options.clone().into())))
-        .enumerate();
-
-    let mut lines = lines
-        .filter(|(_, l)| {
-            if l.trim().is_empty() {
-                return false;
-            }
-            true
-        })
-        .peekable();
+    let literal_blocks = options
+        .literal_blocks
+        .clone()
+        .unwrap_or_else(|| options.dialect.literal_blocks());
+    // Enumerate before filtering to keep the original line index
+    let mut lines = group_literal_blocks(
+        raw_config.lines().map(String::from).enumerate(),
+        &literal_blocks,
+    )?
+    .into_iter()
+    .filter(|(_, l)| filter_line(l, Some(options.clone().into())))
+    .map(|(i, l)| {
+        if options.normalize_whitespace {
+            (i, normalize_whitespace(&l))
+        } else {
+            (i, l)
+        }
+    })
+    .peekable();
+
+    match options.dialect {
+        Dialect::Indent => process_next_indent_level(&mut lines, &mut parent)?,
+        Dialect::Brace => process_brace_blocks(&mut lines, &mut parent)?,
+        Dialect::Set => process_set_commands(&mut lines, &mut parent)?,
+        Dialect::Fortios => process_fortios_blocks(&mut lines, &mut parent)?,
+        Dialect::Vrp => process_vrp_views(&mut lines, &mut parent)?,
+    }
 
-    process_next_indent_level(&mut lines, &mut parent)?;
+    let spans = line_spans(raw_config);
+    let mut items = parent.get_items().clone();
+    set_items_source(&mut items, &spans, raw_config);
+    parent.set_items(&items);
 
     if !options.ignore_options {
         process_fcc_options(&mut parent)?;
@@ -56,6 +81,20 @@ where
     Ok(parent)
 }
 
+fn set_items_source(items: &mut [FlatConfigItem], spans: &[Span], raw_config: &str) {
+    for item in items.iter_mut() {
+        let mut span = spans[item.get_index()];
+        // Literal blocks end on a following line
+        let nb_lines = item.get_item_key().matches('\n').count();
+        span.end = spans[item.get_index() + nb_lines].end;
+        item.set_source(span, &raw_config[span.start..span.end]);
+
+        if let FlatConfigItem::Parent(ref mut parent) = item {
+            set_items_source(&mut parent.items, spans, raw_config);
+        }
+    }
+}
+
 pub(crate) fn process_next_indent_level(
     vals: &mut Peekable<impl Iterator<Item = (usize, String)> + Clone>,
     previous_parent: &mut dyn ItemsContainer,
@@ -99,15 +138,23 @@ pub(crate) fn process_next_indent_level(
             Ordering::Greater => {
                 if previous_parent.get_items().is_empty() {
                     // As this is call when indent change, previous parent can't be empty
-                    return Err(ParseError::BadIndentation(String::from(next_val)));
+                    return Err(ParseError::BadIndentation(
+                        String::from(next_val),
+                        Position::new(i + 1, indent + 1),
+                    ));
                 }
                 trace!("next value: indent {}, found: {}, compare with previous indent {}: get or create parent", indent, next_val, previous_parent.get_indent());
                 let previous_item = previous_parent.pop_last_item().unwrap();
                 let mut parent = match previous_item {
-                    FlatConfigItem::Line(line) => FlatConfigParent::new(*i, indent, line.line),
+                    FlatConfigItem::Line(line) => {
+                        FlatConfigParent::new(line.index, indent, line.line)
+                    }
                     FlatConfigItem::Parent(_) => {
                         trace!("next value: indent {}, found: {}, compare with previous indent {}: already a parent", indent, next_val, previous_parent.get_indent());
-                        return Err(ParseError::BadIndentation(String::from(next_val)));
+                        return Err(ParseError::BadIndentation(
+                            String::from(next_val),
+                            Position::new(i + 1, indent + 1),
+                        ));
                     }
                 };
                 process_next_indent_level(vals, &mut parent)?;
@@ -204,7 +251,7 @@ mod tests {
             .peekable();
 
         let err = process_next_indent_level(&mut lines, &mut config).unwrap_err();
-        assert!(matches!(err, ParseError::BadIndentation(_)));
+        assert!(matches!(err, ParseError::BadIndentation(_, _)));
     }
 
     #[test]
@@ -217,7 +264,7 @@ mod tests {
             .peekable();
 
         let err = process_next_indent_level(&mut lines, &mut config).unwrap_err();
-        assert!(matches!(err, ParseError::BadIndentation(_)));
+        assert!(matches!(err, ParseError::BadIndentation(_, _)));
     }
 
     #[test]
@@ -235,6 +282,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_configuration_original_index() {
+        let raw = concat!(
+            "#[regex]\n",
+            "line 1\n",
+            "\n",
+            " line 2\n",
+            "#[state=absent]\n",
+            "line 3",
+        );
+
+        let config: FlatConfig = parse_configuration(raw, None).unwrap();
+
+        assert_eq!(config.items[0].get_index(), 1);
+        if let FlatConfigItem::Parent(parent) = &config.items[0] {
+            assert_eq!(parent.items[0].get_index(), 3);
+        }
+        assert_eq!(config.items[1].get_index(), 5);
+    }
+
+    #[test]
+    fn test_parse_configuration_source() {
+        let raw = concat!("line 1\r\n", "\n", "  line 2\n", "line 3",);
+
+        let config: FlatConfig = parse_configuration(raw, None).unwrap();
+
+        let item = config.items.first().unwrap();
+        assert_eq!(item.get_span().line, 1);
+        if let FlatConfigItem::Parent(parent) = item {
+            let child = parent.items.first().unwrap();
+            let span = child.get_span();
+            assert_eq!(span.line, 3);
+            assert_eq!(child.get_raw(), "  line 2");
+            assert_eq!(&raw[span.start..span.end], "  line 2");
+        } else {
+            panic!()
+        }
+        assert_eq!(config.items[1].get_span().line, 4);
+    }
+
+    #[test]
+    fn test_parse_configuration_error_position() {
+        let raw = concat!("line 1\n", "  line 2\n", " line 3",);
+
+        let err = parse_configuration::<FlatConfig>(raw, None).unwrap_err();
+        assert_eq!(err.position(), Position::new(3, 2));
+
+        let raw = concat!("line 1\n", "#[state=unknown]\n", "line 2",);
+
+        let err = parse_configuration::<FlatConfig>(raw, None).unwrap_err();
+        assert!(matches!(err, ParseError::ComplianceOption(_, _)));
+        // Column of the invalid argument
+        assert_eq!(err.position(), Position::new(2, 9));
+
+        let raw = concat!("line 1\n", " #[regex, stat=absent]\n", " line 2",);
+
+        let err = parse_configuration::<FlatConfig>(raw, None).unwrap_err();
+        assert_eq!(err.position(), Position::new(2, 11));
+
+        let raw = concat!("line 1\n", "  #[regex state=absent]\n", "  line 2",);
+
+        let err = parse_configuration::<FlatConfig>(raw, None).unwrap_err();
+        assert_eq!(err.position(), Position::new(2, 11));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 11: Invalid option syntax: unexpected \"s\", expected \",\" or \"]\""
+        );
+    }
+
     #[test]
     fn test_parse_configuration_ignore_options() {
         let options = ParseOption {
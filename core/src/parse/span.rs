@@ -0,0 +1,80 @@
+use std::fmt;
+
+/// Location of an item in the original text.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Byte offset of the first character of the line.
+    pub start: usize,
+    /// Byte offset right after the last character, line ending excluded.
+    pub end: usize,
+}
+
+impl Span {
+    pub fn position(&self, column: usize) -> Position {
+        Position {
+            line: self.line,
+            column,
+        }
+    }
+}
+
+/// Line and column, both starting at 1.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Span of every line of the text, as enumerated by `str::lines`.
+pub(crate) fn line_spans(raw: &str) -> Vec<Span> {
+    let mut spans = vec![];
+    let mut start = 0;
+
+    for (i, line) in raw.split_inclusive('\n').enumerate() {
+        let content = match line.strip_suffix('\n') {
+            Some(l) => l.strip_suffix('\r').unwrap_or(l),
+            None => line,
+        };
+        spans.push(Span {
+            line: i + 1,
+            start,
+            end: start + content.len(),
+        });
+        start += line.len();
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_spans() {
+        let raw = "line 1\r\n  line 2\n\nline 4";
+        let spans = line_spans(raw);
+
+        assert_eq!(spans.len(), raw.lines().count());
+        for (span, line) in spans.iter().zip(raw.lines()) {
+            assert_eq!(&raw[span.start..span.end], line);
+        }
+        assert_eq!(spans[3].line, 4);
+    }
+}